@@ -0,0 +1,160 @@
+use crate::Order;
+use crate::TurnContext;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+#[derive(Debug, Copy, Clone)]
+pub struct TimeBudget {
+    deadline: Option<Instant>,
+}
+
+impl TimeBudget {
+    pub fn new(duration: Duration) -> Self {
+        Self::until(Instant::now() + duration)
+    }
+
+    pub fn until(deadline: Instant) -> Self {
+        Self {
+            deadline: Some(deadline),
+        }
+    }
+
+    pub fn unlimited() -> Self {
+        Self { deadline: None }
+    }
+
+    pub fn from_context(context: &TurnContext, margin: Duration) -> Self {
+        match context.deadline {
+            Some(deadline) => Self::until(deadline.checked_sub(margin).unwrap_or(deadline)),
+            None => Self::unlimited(),
+        }
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.deadline.map_or(Duration::MAX, |deadline| {
+            deadline.saturating_duration_since(Instant::now())
+        })
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.deadline
+            .is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    pub fn share(&self, fraction: f64) -> TimeBudget {
+        let fraction = fraction.clamp(0.0, 1.0);
+        match self.deadline {
+            Some(_) => TimeBudget::new(self.remaining().mul_f64(fraction)),
+            None => TimeBudget::unlimited(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OrderDraft {
+    orders: Arc<Mutex<Vec<Order>>>,
+}
+
+impl OrderDraft {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn publish(&self, orders: Vec<Order>) {
+        if let Ok(mut draft) = self.orders.lock() {
+            *draft = orders;
+        }
+    }
+
+    pub fn take(&self) -> Vec<Order> {
+        self.orders
+            .lock()
+            .map(|mut draft| std::mem::take(&mut *draft))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Direction;
+
+    #[test]
+    fn given_future_deadline_when_is_exhausted_then_false() {
+        let budget = TimeBudget::new(Duration::from_secs(10));
+
+        assert!(!budget.is_exhausted());
+        assert!(budget.remaining() > Duration::from_secs(9));
+    }
+
+    #[test]
+    fn given_past_deadline_when_is_exhausted_then_true() {
+        let budget = TimeBudget::new(Duration::from_secs(0));
+
+        assert!(budget.is_exhausted());
+        assert_eq!(budget.remaining(), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn given_context_when_from_context_then_deadline_is_reduced_by_margin() {
+        let deadline = Instant::now() + Duration::from_secs(1);
+        let context = TurnContext {
+            turn: 1,
+            turns: 500,
+            deadline: Some(deadline),
+            draft: OrderDraft::new(),
+        };
+
+        let budget = TimeBudget::from_context(&context, Duration::from_millis(200));
+
+        assert_eq!(
+            budget.deadline(),
+            Some(deadline - Duration::from_millis(200))
+        );
+    }
+
+    #[test]
+    fn given_context_without_deadline_when_from_context_then_budget_is_unlimited() {
+        let context = TurnContext {
+            turn: 1,
+            turns: 500,
+            deadline: None,
+            draft: OrderDraft::new(),
+        };
+
+        let budget = TimeBudget::from_context(&context, Duration::from_millis(200));
+
+        assert_eq!(budget.deadline(), None);
+        assert!(!budget.is_exhausted());
+        assert!(!budget.share(0.5).is_exhausted());
+    }
+
+    #[test]
+    fn when_share_then_sub_budget_ends_before_budget() {
+        let budget = TimeBudget::new(Duration::from_secs(10));
+
+        let share = budget.share(0.5);
+
+        assert!(share.deadline() < budget.deadline());
+        assert!(share.remaining() <= Duration::from_secs(5));
+    }
+
+    #[test]
+    fn given_published_orders_when_take_then_return_latest_orders_from_any_clone() {
+        let draft = OrderDraft::new();
+        let publisher = draft.clone();
+        publisher.publish(vec![Order::new(1, 2, Direction::N)]);
+        publisher.publish(vec![Order::new(3, 4, Direction::S)]);
+
+        let orders = draft.take();
+
+        assert_eq!(orders, vec![Order::new(3, 4, Direction::S)]);
+        assert!(draft.take().is_empty());
+    }
+}
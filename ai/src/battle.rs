@@ -0,0 +1,441 @@
+use crate::CombatSimulator;
+use crate::Config;
+use crate::Direction;
+use crate::MoveResolver;
+use crate::PlayerEntity;
+use crate::Position;
+use crate::TimeBudget;
+use crate::World;
+use std::collections::BTreeSet;
+
+const IMPROVEMENT_PASSES: usize = 2;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Tactic {
+    Attack,
+    Hold,
+    Retreat,
+}
+
+impl Tactic {
+    pub const ALL: [Tactic; 3] = [Tactic::Attack, Tactic::Hold, Tactic::Retreat];
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Skirmish {
+    pub own: Vec<Position>,
+    pub enemies: Vec<PlayerEntity>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BattlePlan {
+    pub moves: Vec<(Position, Option<Direction>)>,
+    pub killed: usize,
+    pub lost: usize,
+}
+
+impl BattlePlan {
+    pub fn score(&self) -> i32 {
+        self.killed as i32 - self.lost as i32
+    }
+
+    fn is_better_than(&self, other: &BattlePlan) -> bool {
+        (self.score(), -(self.lost as i32)) > (other.score(), -(other.lost as i32))
+    }
+}
+
+#[derive(Debug)]
+pub struct BattlePlanner {
+    combat: CombatSimulator,
+    engage_radius2: i32,
+}
+
+impl BattlePlanner {
+    pub fn new(config: &Config) -> Self {
+        let engage_radius = (config.attack_radius2 as f64).sqrt() + 2.0;
+        Self {
+            combat: CombatSimulator::from_config(config),
+            engage_radius2: (engage_radius * engage_radius) as i32,
+        }
+    }
+
+    pub fn skirmishes(&self, world: &World, own: &[Position]) -> Vec<Skirmish> {
+        let geometry = world.geometry();
+        let engaged = |a: Position, b: Position| geometry.distance2(a, b) <= self.engage_radius2;
+
+        let enemies: Vec<PlayerEntity> = world
+            .enemy_ants()
+            .filter(|e| own.iter().any(|&o| engaged(o, e.pos)))
+            .copied()
+            .collect();
+        let own: Vec<Position> = own
+            .iter()
+            .copied()
+            .filter(|&o| enemies.iter().any(|e| engaged(o, e.pos)))
+            .collect();
+
+        let positions: Vec<Position> = own
+            .iter()
+            .copied()
+            .chain(enemies.iter().map(|e| e.pos))
+            .collect();
+        let mut groups: Vec<usize> = (0..positions.len()).collect();
+        for i in 0..positions.len() {
+            for j in i + 1..positions.len() {
+                if engaged(positions[i], positions[j]) {
+                    let (a, b) = (find(&mut groups, i), find(&mut groups, j));
+                    groups[a] = b;
+                }
+            }
+        }
+
+        let mut skirmishes: Vec<(usize, Skirmish)> = vec![];
+        for (i, &pos) in positions.iter().enumerate() {
+            let group = find(&mut groups, i);
+            let index = match skirmishes.iter().position(|(g, _)| *g == group) {
+                Some(index) => index,
+                None => {
+                    skirmishes.push((
+                        group,
+                        Skirmish {
+                            own: vec![],
+                            enemies: vec![],
+                        },
+                    ));
+                    skirmishes.len() - 1
+                }
+            };
+            if i < own.len() {
+                skirmishes[index].1.own.push(pos);
+            } else {
+                skirmishes[index].1.enemies.push(enemies[i - own.len()]);
+            }
+        }
+        skirmishes.into_iter().map(|(_, s)| s).collect()
+    }
+
+    pub fn plan(&self, world: &World, skirmish: &Skirmish, budget: &TimeBudget) -> BattlePlan {
+        let enemy_positions: Vec<Position> = skirmish.enemies.iter().map(|e| e.pos).collect();
+        let responses: Vec<Vec<PlayerEntity>> = Tactic::ALL
+            .iter()
+            .map(|&tactic| {
+                let moves = tactic_moves(world, &enemy_positions, &skirmish.own, tactic);
+                skirmish
+                    .enemies
+                    .iter()
+                    .zip(moves)
+                    .map(|(e, (_, d))| PlayerEntity {
+                        id: e.id,
+                        pos: destination(world, e.pos, d),
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let mut best: Option<BattlePlan> = None;
+        for &tactic in &Tactic::ALL {
+            let moves = tactic_moves(world, &skirmish.own, &enemy_positions, tactic);
+            let plan = self.evaluate(world, moves, &responses);
+            if best.as_ref().is_none_or(|b| plan.is_better_than(b)) {
+                best = Some(plan);
+            }
+        }
+        let mut best = best.unwrap_or(BattlePlan {
+            moves: vec![],
+            killed: 0,
+            lost: 0,
+        });
+
+        for _ in 0..IMPROVEMENT_PASSES {
+            let mut improved = false;
+            for i in 0..best.moves.len() {
+                if budget.is_exhausted() {
+                    return best;
+                }
+                for candidate in candidate_moves(world, best.moves[i].0) {
+                    let mut moves = best.moves.clone();
+                    moves[i].1 = candidate;
+                    if !has_unique_destinations(world, &moves) {
+                        continue;
+                    }
+                    let plan = self.evaluate(world, moves, &responses);
+                    if plan.is_better_than(&best) {
+                        best = plan;
+                        improved = true;
+                    }
+                }
+            }
+            if !improved {
+                break;
+            }
+        }
+        best
+    }
+
+    pub fn fight(
+        &self,
+        world: &World,
+        moves: &mut MoveResolver,
+        budget: &TimeBudget,
+    ) -> Vec<BattlePlan> {
+        let own: Vec<Position> = moves.idle_ants().collect();
+        let plans: Vec<BattlePlan> = self
+            .skirmishes(world, &own)
+            .iter()
+            .take_while(|_| !budget.is_exhausted())
+            .map(|s| self.plan(world, s, budget))
+            .collect();
+        for plan in &plans {
+            for &(ant, direction) in &plan.moves {
+                if direction.is_none() {
+                    moves.hold(ant);
+                }
+            }
+        }
+        for plan in &plans {
+            for &(ant, direction) in &plan.moves {
+                if let Some(direction) = direction {
+                    moves.order(ant, direction);
+                }
+            }
+        }
+        plans
+    }
+
+    fn evaluate(
+        &self,
+        world: &World,
+        moves: Vec<(Position, Option<Direction>)>,
+        responses: &[Vec<PlayerEntity>],
+    ) -> BattlePlan {
+        let own: Vec<PlayerEntity> = moves
+            .iter()
+            .map(|&(pos, d)| PlayerEntity {
+                id: 0,
+                pos: destination(world, pos, d),
+            })
+            .collect();
+
+        let mut worst: Option<(usize, usize)> = None;
+        for enemies in responses {
+            let ants: Vec<PlayerEntity> = own.iter().chain(enemies.iter()).copied().collect();
+            let dies = self.combat.dies(&ants);
+            let lost = dies[..own.len()].iter().filter(|&&d| d).count();
+            let killed = dies[own.len()..].iter().filter(|&&d| d).count();
+            let score = killed as i32 - lost as i32;
+            if worst.is_none_or(|(k, l)| score < k as i32 - l as i32) {
+                worst = Some((killed, lost));
+            }
+        }
+        let (killed, lost) = worst.unwrap_or((0, 0));
+        BattlePlan {
+            moves,
+            killed,
+            lost,
+        }
+    }
+}
+
+fn find(groups: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while groups[root] != root {
+        root = groups[root];
+    }
+    groups[i] = root;
+    root
+}
+
+fn destination(world: &World, pos: Position, direction: Option<Direction>) -> Position {
+    match direction {
+        Some(direction) => world.geometry().step(pos, direction),
+        None => pos,
+    }
+}
+
+fn candidate_moves(world: &World, pos: Position) -> Vec<Option<Direction>> {
+    std::iter::once(None)
+        .chain(
+            Direction::ALL
+                .iter()
+                .filter(|&&d| !world.is_water(world.geometry().step(pos, d)))
+                .map(|&d| Some(d)),
+        )
+        .collect()
+}
+
+fn has_unique_destinations(world: &World, moves: &[(Position, Option<Direction>)]) -> bool {
+    let mut destinations = BTreeSet::new();
+    moves
+        .iter()
+        .all(|&(pos, d)| destinations.insert(destination(world, pos, d)))
+}
+
+fn tactic_moves(
+    world: &World,
+    ants: &[Position],
+    opponents: &[Position],
+    tactic: Tactic,
+) -> Vec<(Position, Option<Direction>)> {
+    let geometry = world.geometry();
+    let occupied: BTreeSet<Position> = ants.iter().copied().collect();
+    let mut destinations: BTreeSet<Position> = BTreeSet::new();
+    let mut moves = vec![];
+    for &ant in ants {
+        let nearest = opponents
+            .iter()
+            .copied()
+            .min_by_key(|&o| geometry.distance2(ant, o));
+        let preferred = match (tactic, nearest) {
+            (Tactic::Attack, Some(target)) => geometry.directions(ant, target),
+            (Tactic::Retreat, Some(target)) => geometry
+                .directions(ant, target)
+                .into_iter()
+                .map(Direction::opposite)
+                .collect(),
+            _ => vec![],
+        };
+        let direction = preferred.into_iter().find(|&d| {
+            let next = geometry.step(ant, d);
+            !world.is_water(next) && !occupied.contains(&next) && !destinations.contains(&next)
+        });
+        destinations.insert(destination(world, ant, direction));
+        moves.push((ant, direction));
+    }
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use crate::test_support::a_config;
+    use crate::test_support::an_empty_turn_info;
+    use crate::test_support::entity;
+    use crate::test_support::pos;
+    use crate::TurnInfo;
+    use std::time::Duration;
+
+    fn a_budget() -> TimeBudget {
+        TimeBudget::new(Duration::from_secs(10))
+    }
+
+    fn a_battle_config() -> Config {
+        Config {
+            width: 30,
+            height: 30,
+            view_radius2: 77,
+            ..a_config()
+        }
+    }
+
+    fn a_world(ants: Vec<PlayerEntity>) -> World {
+        test_support::a_world(
+            &a_battle_config(),
+            &TurnInfo {
+                ant: ants,
+                ..an_empty_turn_info()
+            },
+        )
+    }
+
+    fn own_positions(world: &World) -> Vec<Position> {
+        world.own_ants().map(|a| a.pos).collect()
+    }
+
+    #[test]
+    fn given_separate_fights_when_skirmishes_then_split_into_independent_skirmishes() {
+        let world = a_world(vec![
+            entity(0, 5, 5),
+            entity(1, 5, 8),
+            entity(0, 20, 20),
+            entity(1, 22, 22),
+            entity(0, 12, 12),
+        ]);
+        let planner = BattlePlanner::new(&a_battle_config());
+
+        let skirmishes = planner.skirmishes(&world, &own_positions(&world));
+
+        assert_eq!(skirmishes.len(), 2);
+        assert_eq!(skirmishes[0].own, vec![pos(5, 5)]);
+        assert_eq!(skirmishes[0].enemies, vec![entity(1, 5, 8)]);
+        assert_eq!(skirmishes[1].own, vec![pos(20, 20)]);
+    }
+
+    #[test]
+    fn given_one_on_one_when_plan_then_do_not_lose_an_ant() {
+        let world = a_world(vec![entity(0, 5, 5), entity(1, 5, 9)]);
+        let planner = BattlePlanner::new(&a_battle_config());
+        let skirmish = &planner.skirmishes(&world, &own_positions(&world))[0];
+
+        let plan = planner.plan(&world, skirmish, &a_budget());
+
+        assert_eq!(plan.lost, 0);
+        assert_ne!(plan.moves[0].1, Some(Direction::S));
+    }
+
+    #[test]
+    fn given_outnumbered_enemy_when_plan_then_attack_without_losses() {
+        let world = a_world(vec![
+            entity(0, 4, 5),
+            entity(0, 5, 5),
+            entity(0, 6, 5),
+            entity(1, 5, 8),
+        ]);
+        let planner = BattlePlanner::new(&a_battle_config());
+        let skirmish = &planner.skirmishes(&world, &own_positions(&world))[0];
+
+        let plan = planner.plan(&world, skirmish, &a_budget());
+
+        assert_eq!(plan.lost, 0);
+        assert!(plan.moves.iter().any(|&(_, d)| d == Some(Direction::S)));
+    }
+
+    #[test]
+    fn given_enemy_in_range_when_plan_then_kill_it_without_losses() {
+        let world = a_world(vec![entity(0, 4, 5), entity(0, 6, 5), entity(1, 5, 7)]);
+        let planner = BattlePlanner::new(&a_battle_config());
+        let skirmish = &planner.skirmishes(&world, &own_positions(&world))[0];
+
+        let plan = planner.plan(&world, skirmish, &a_budget());
+
+        assert_eq!(
+            plan.moves,
+            vec![
+                (pos(4, 5), Some(Direction::S)),
+                (pos(6, 5), Some(Direction::S))
+            ]
+        );
+        assert_eq!(plan.killed, 1);
+        assert_eq!(plan.lost, 0);
+    }
+
+    #[test]
+    fn given_exhausted_budget_when_fight_then_plan_nothing() {
+        let world = a_world(vec![entity(0, 5, 5), entity(1, 5, 8)]);
+        let planner = BattlePlanner::new(&a_battle_config());
+        let mut moves = MoveResolver::new(&world);
+
+        let plans = planner.fight(&world, &mut moves, &TimeBudget::new(Duration::from_secs(0)));
+
+        assert!(plans.is_empty());
+        assert!(moves.is_idle(pos(5, 5)));
+    }
+
+    #[test]
+    fn given_skirmish_when_fight_then_issue_orders_for_skirmish_ants_only() {
+        let world = a_world(vec![
+            entity(0, 4, 5),
+            entity(0, 5, 5),
+            entity(0, 6, 5),
+            entity(1, 5, 8),
+            entity(0, 20, 20),
+        ]);
+        let planner = BattlePlanner::new(&a_battle_config());
+        let mut moves = MoveResolver::new(&world);
+
+        planner.fight(&world, &mut moves, &a_budget());
+
+        assert!(moves.is_idle(pos(20, 20)));
+        assert!(!moves.is_idle(pos(5, 5)));
+    }
+}
@@ -0,0 +1,139 @@
+use crate::Config;
+use crate::Geometry;
+use crate::PlayerEntity;
+
+#[derive(Debug, Copy, Clone)]
+pub struct CombatSimulator {
+    geometry: Geometry,
+    attack_radius2: i32,
+}
+
+impl CombatSimulator {
+    pub fn new(geometry: Geometry, attack_radius2: i32) -> Self {
+        Self {
+            geometry,
+            attack_radius2,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(Geometry::from_config(config), config.attack_radius2)
+    }
+
+    pub fn in_range(&self, a: &PlayerEntity, b: &PlayerEntity) -> bool {
+        self.geometry.distance2(a.pos, b.pos) <= self.attack_radius2
+    }
+
+    pub fn focus(&self, ants: &[PlayerEntity]) -> Vec<usize> {
+        ants.iter()
+            .map(|a| {
+                ants.iter()
+                    .filter(|b| b.id != a.id && self.in_range(a, b))
+                    .count()
+            })
+            .collect()
+    }
+
+    pub fn dies(&self, ants: &[PlayerEntity]) -> Vec<bool> {
+        let focus = self.focus(ants);
+        ants.iter()
+            .enumerate()
+            .map(|(i, a)| {
+                focus[i] > 0
+                    && ants
+                        .iter()
+                        .enumerate()
+                        .any(|(j, b)| b.id != a.id && self.in_range(a, b) && focus[j] <= focus[i])
+            })
+            .collect()
+    }
+
+    pub fn dead_ants(&self, ants: &[PlayerEntity]) -> Vec<PlayerEntity> {
+        self.dies(ants)
+            .into_iter()
+            .zip(ants.iter())
+            .filter(|(dead, _)| *dead)
+            .map(|(_, &a)| a)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::entity;
+
+    fn a_simulator() -> CombatSimulator {
+        CombatSimulator::new(Geometry::new(20, 20), 5)
+    }
+
+    #[test]
+    fn given_one_on_one_in_range_when_dies_then_both_die() {
+        let ants = vec![entity(0, 5, 5), entity(1, 7, 6)];
+
+        let dies = a_simulator().dies(&ants);
+
+        assert_eq!(dies, vec![true, true]);
+    }
+
+    #[test]
+    fn given_ants_out_of_range_when_dies_then_nobody_dies() {
+        let ants = vec![entity(0, 5, 5), entity(1, 7, 7)];
+
+        let dies = a_simulator().dies(&ants);
+
+        assert_eq!(dies, vec![false, false]);
+    }
+
+    #[test]
+    fn given_two_against_one_when_dies_then_only_lone_ant_dies() {
+        let ants = vec![entity(0, 5, 5), entity(0, 6, 5), entity(1, 5, 7)];
+
+        let dies = a_simulator().dies(&ants);
+
+        assert_eq!(dies, vec![false, false, true]);
+    }
+
+    #[test]
+    fn given_same_player_ants_close_together_when_dies_then_nobody_dies() {
+        let ants = vec![entity(0, 5, 5), entity(0, 6, 5)];
+
+        let dies = a_simulator().dies(&ants);
+
+        assert_eq!(dies, vec![false, false]);
+    }
+
+    #[test]
+    fn given_three_players_in_range_of_each_other_when_dies_then_all_die() {
+        let ants = vec![entity(0, 5, 5), entity(1, 6, 5), entity(2, 5, 6)];
+
+        let dead = a_simulator().dead_ants(&ants);
+
+        assert_eq!(dead.len(), 3);
+    }
+
+    #[test]
+    fn given_line_of_ants_when_dies_then_apply_focus_rule() {
+        // The ants at both ends are only in range of an ant that is itself
+        // engaged with two enemies, so they survive while the middle ones die.
+        let ants = vec![
+            entity(1, 3, 5),
+            entity(0, 5, 5),
+            entity(1, 7, 5),
+            entity(0, 9, 5),
+        ];
+
+        let dies = a_simulator().dies(&ants);
+
+        assert_eq!(dies, vec![false, true, true, false]);
+    }
+
+    #[test]
+    fn given_enemies_across_map_edge_when_dies_then_use_wrapped_distance() {
+        let ants = vec![entity(0, 0, 0), entity(1, 19, 19)];
+
+        let dead = a_simulator().dead_ants(&ants);
+
+        assert_eq!(dead, ants);
+    }
+}
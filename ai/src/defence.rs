@@ -0,0 +1,208 @@
+use crate::multi_source_bfs_within;
+use crate::optimal_matching;
+use crate::DistanceField;
+use crate::MoveResolver;
+use crate::Position;
+use crate::World;
+use std::iter;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Threat {
+    pub hill: Position,
+    pub enemies: Vec<(Position, i32)>,
+}
+
+impl Threat {
+    pub fn level(&self) -> usize {
+        self.enemies.len()
+    }
+
+    pub fn closest(&self) -> Option<i32> {
+        self.enemies.iter().map(|&(_, d)| d).min()
+    }
+}
+
+#[derive(Debug)]
+pub struct HillDefender {
+    radius: i32,
+    threats: Vec<Threat>,
+    guards: Vec<Position>,
+}
+
+impl HillDefender {
+    pub fn new(radius: i32) -> Self {
+        Self {
+            radius,
+            threats: vec![],
+            guards: vec![],
+        }
+    }
+
+    pub fn threats(&self) -> &[Threat] {
+        &self.threats
+    }
+
+    pub fn guards(&self) -> &[Position] {
+        &self.guards
+    }
+
+    pub fn assess(&mut self, world: &World) -> &[Threat] {
+        let field = self.hill_field(world);
+        self.threats = assess_threats(world, &field);
+        &self.threats
+    }
+
+    pub fn defend(&mut self, world: &World, moves: &mut MoveResolver) -> Vec<Position> {
+        let hill_field = self.hill_field(world);
+        self.threats = assess_threats(world, &hill_field);
+        self.guards.clear();
+
+        let enemies: Vec<(Position, i32)> = self
+            .threats
+            .iter()
+            .flat_map(|t| t.enemies.iter().copied())
+            .collect();
+        if enemies.is_empty() {
+            return vec![];
+        }
+
+        let ants: Vec<Position> = moves.idle_ants().collect();
+        let fields: Vec<DistanceField> = enemies
+            .iter()
+            .map(|&(e, d)| multi_source_bfs_within(world, iter::once(e), d + self.radius))
+            .collect();
+        let costs: Vec<Vec<Option<i32>>> = enemies
+            .iter()
+            .zip(fields.iter())
+            .map(|(&(_, enemy_distance), field)| {
+                ants.iter()
+                    .map(|&a| match hill_field.distance(a) {
+                        Some(d) if d <= enemy_distance => field.distance(a),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .collect();
+
+        for (e, a) in optimal_matching(&costs) {
+            let ant = ants[a];
+            let ordered = match fields[e].direction_to_origin(ant) {
+                Some(direction) => moves.order(ant, direction),
+                None => moves.hold(ant),
+            };
+            if ordered {
+                self.guards.push(ant);
+            }
+        }
+        self.guards.clone()
+    }
+
+    fn hill_field(&self, world: &World) -> DistanceField {
+        multi_source_bfs_within(world, world.own_hills().map(|h| h.pos), self.radius)
+    }
+}
+
+fn assess_threats(world: &World, field: &DistanceField) -> Vec<Threat> {
+    let mut threats: Vec<Threat> = vec![];
+    for enemy in world.enemy_ants() {
+        if let (Some(hill), Some(distance)) = (field.origin(enemy.pos), field.distance(enemy.pos)) {
+            match threats.iter_mut().find(|t| t.hill == hill) {
+                Some(threat) => threat.enemies.push((enemy.pos, distance)),
+                None => threats.push(Threat {
+                    hill,
+                    enemies: vec![(enemy.pos, distance)],
+                }),
+            }
+        }
+    }
+    threats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use crate::test_support::a_config;
+    use crate::test_support::an_empty_turn_info;
+    use crate::test_support::entity;
+    use crate::test_support::pos;
+    use crate::Config;
+    use crate::Direction;
+    use crate::PlayerEntity;
+    use crate::TurnInfo;
+
+    fn a_world(ant_hill: Vec<PlayerEntity>, ant: Vec<PlayerEntity>) -> World {
+        test_support::a_world(
+            &Config {
+                width: 40,
+                height: 40,
+                ..a_config()
+            },
+            &TurnInfo {
+                ant_hill,
+                ant,
+                ..an_empty_turn_info()
+            },
+        )
+    }
+
+    #[test]
+    fn given_enemies_near_hill_when_assess_then_report_threat_for_hill() {
+        let world = a_world(
+            vec![entity(0, 10, 10), entity(0, 30, 30)],
+            vec![entity(1, 10, 15), entity(2, 12, 10), entity(1, 20, 20)],
+        );
+        let mut defender = HillDefender::new(8);
+
+        let threats = defender.assess(&world);
+
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].hill, pos(10, 10));
+        assert_eq!(threats[0].level(), 2);
+        assert_eq!(threats[0].closest(), Some(2));
+    }
+
+    #[test]
+    fn given_threat_when_defend_then_send_one_interceptor_per_enemy() {
+        let world = a_world(
+            vec![entity(0, 10, 10)],
+            vec![
+                entity(1, 10, 16),
+                entity(0, 10, 11),
+                entity(0, 9, 10),
+                entity(0, 10, 20),
+            ],
+        );
+        let mut defender = HillDefender::new(8);
+        let mut moves = MoveResolver::new(&world);
+
+        let guards = defender.defend(&world, &mut moves);
+
+        assert_eq!(guards, vec![pos(10, 11)]);
+        assert!(moves.is_idle(pos(9, 10)));
+        assert!(moves.is_idle(pos(10, 20)));
+        let orders = moves.resolve();
+        assert_eq!(orders[0].pos, pos(10, 11));
+        assert_eq!(orders[0].direction, Direction::S);
+    }
+
+    #[test]
+    fn given_threat_passed_when_defend_then_release_guards() {
+        let mut defender = HillDefender::new(8);
+        let world = a_world(
+            vec![entity(0, 10, 10)],
+            vec![entity(1, 10, 16), entity(0, 10, 11)],
+        );
+        defender.defend(&world, &mut MoveResolver::new(&world));
+        let world = a_world(
+            vec![entity(0, 10, 10)],
+            vec![entity(1, 10, 30), entity(0, 10, 12)],
+        );
+
+        let guards = defender.defend(&world, &mut MoveResolver::new(&world));
+
+        assert!(guards.is_empty());
+        assert!(defender.guards().is_empty());
+        assert!(defender.threats().is_empty());
+    }
+}
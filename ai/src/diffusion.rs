@@ -0,0 +1,256 @@
+use crate::Config;
+use crate::Direction;
+use crate::EndInfo;
+use crate::Geometry;
+use crate::MoveResolver;
+use crate::Order;
+use crate::Position;
+use crate::TurnContext;
+use crate::TurnInfo;
+use crate::TurnTaker;
+use crate::World;
+
+const ITERATIONS: usize = 40;
+const DECAY: f64 = 0.9;
+const ANT_DAMPING: f64 = 0.5;
+const FOOD_SCENT: f64 = 1000.0;
+const ENEMY_HILL_SCENT: f64 = 2000.0;
+const ENEMY_ANT_SCENT: f64 = 200.0;
+const UNSEEN_SCENT: f64 = 300.0;
+const STALE_SCENT_PER_TURN: f64 = 5.0;
+
+#[derive(Debug)]
+pub struct ScentMap {
+    geometry: Geometry,
+    values: Vec<f64>,
+}
+
+impl ScentMap {
+    pub fn new(geometry: Geometry) -> Self {
+        Self {
+            geometry,
+            values: vec![0.0; geometry.size()],
+        }
+    }
+
+    pub fn scent(&self, pos: Position) -> f64 {
+        self.values[self.geometry.index(pos)]
+    }
+
+    pub fn diffuse(&mut self, world: &World, iterations: usize) {
+        let sources = self.sources(world);
+        let ants: Vec<usize> = world
+            .own_ants()
+            .map(|a| self.geometry.index(a.pos))
+            .collect();
+
+        let mut next = vec![0.0; self.values.len()];
+        for _ in 0..iterations {
+            for (i, value) in next.iter_mut().enumerate() {
+                let pos = self.geometry.position(i);
+                *value = if world.is_water(pos) {
+                    0.0
+                } else if let Some(source) = sources[i] {
+                    source
+                } else {
+                    let sum: f64 = self
+                        .geometry
+                        .neighbours(pos)
+                        .iter()
+                        .map(|&n| self.values[self.geometry.index(n)])
+                        .sum();
+                    DECAY * sum / 4.0
+                };
+            }
+            for &i in &ants {
+                next[i] *= ANT_DAMPING;
+            }
+            std::mem::swap(&mut self.values, &mut next);
+        }
+    }
+
+    fn sources(&self, world: &World) -> Vec<Option<f64>> {
+        let mut sources: Vec<Option<f64>> = (0..self.values.len())
+            .map(|i| match world.last_seen(self.geometry.position(i)) {
+                None => Some(UNSEEN_SCENT),
+                Some(turn) if turn < world.turn() => {
+                    Some(f64::from(world.turn() - turn) * STALE_SCENT_PER_TURN)
+                }
+                Some(_) => None,
+            })
+            .collect();
+        for enemy in world.enemy_ants() {
+            sources[self.geometry.index(enemy.pos)] = Some(ENEMY_ANT_SCENT);
+        }
+        for &food in world.food() {
+            sources[self.geometry.index(food)] = Some(FOOD_SCENT);
+        }
+        for hill in world.enemy_hills() {
+            sources[self.geometry.index(hill.pos)] = Some(ENEMY_HILL_SCENT);
+        }
+        sources
+    }
+}
+
+#[derive(Debug)]
+pub struct DiffusionAgent {
+    world: World,
+    scent: ScentMap,
+}
+
+impl DiffusionAgent {
+    pub fn new(config: &Config) -> Self {
+        let world = World::new(config);
+        let scent = ScentMap::new(*world.geometry());
+        Self { world, scent }
+    }
+
+    pub fn scent(&self) -> &ScentMap {
+        &self.scent
+    }
+}
+
+impl TurnTaker for DiffusionAgent {
+    fn setup(&mut self, config: &Config) {
+        *self = Self::new(config);
+    }
+
+    fn take_turn(&mut self, turn_info: TurnInfo, context: &TurnContext) -> Vec<Order> {
+        self.world.update(context.turn, &turn_info);
+        self.scent.diffuse(&self.world, ITERATIONS);
+
+        let geometry = self.world.geometry();
+        let scent = &self.scent;
+        let mut moves = MoveResolver::new(&self.world);
+        let mut ants: Vec<Position> = moves.idle_ants().collect();
+        ants.sort_by(|a, b| scent.scent(*b).total_cmp(&scent.scent(*a)));
+        for ant in ants {
+            let mut directions: Vec<(f64, Direction)> = Direction::ALL
+                .iter()
+                .map(|&d| (scent.scent(geometry.step(ant, d)), d))
+                .filter(|&(s, _)| s > scent.scent(ant))
+                .collect();
+            directions.sort_by(|a, b| b.0.total_cmp(&a.0));
+            if !directions.into_iter().any(|(_, d)| moves.order(ant, d)) {
+                moves.hold(ant);
+            }
+        }
+        moves.resolve()
+    }
+
+    fn end(&mut self, _end_info: EndInfo) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::a_config;
+    use crate::test_support::a_context;
+    use crate::test_support::an_empty_turn_info;
+    use crate::test_support::own_ants;
+    use crate::test_support::pos;
+
+    fn a_wide_view_config() -> Config {
+        Config {
+            view_radius2: 400,
+            ..a_config()
+        }
+    }
+
+    fn a_turn(water: Vec<Position>, food: Vec<Position>, ants: Vec<Position>) -> TurnInfo {
+        TurnInfo {
+            water,
+            food,
+            ant: own_ants(ants),
+            ..an_empty_turn_info()
+        }
+    }
+
+    #[test]
+    fn given_food_when_diffuse_then_scent_decreases_with_distance() {
+        let config = a_wide_view_config();
+        let mut world = World::new(&config);
+        world.update(1, &a_turn(vec![], vec![pos(5, 5)], vec![pos(15, 15)]));
+        let mut scent = ScentMap::new(*world.geometry());
+
+        scent.diffuse(&world, ITERATIONS);
+
+        assert!(scent.scent(pos(5, 5)) > scent.scent(pos(5, 6)));
+        assert!(scent.scent(pos(5, 6)) > scent.scent(pos(5, 8)));
+    }
+
+    #[test]
+    fn given_water_when_diffuse_then_water_blocks_scent() {
+        let config = a_wide_view_config();
+        let mut world = World::new(&config);
+        world.update(
+            1,
+            &a_turn(vec![pos(5, 6)], vec![pos(5, 5)], vec![pos(15, 15)]),
+        );
+        let mut scent = ScentMap::new(*world.geometry());
+
+        scent.diffuse(&world, ITERATIONS);
+
+        assert_eq!(scent.scent(pos(5, 6)), 0.0);
+    }
+
+    #[test]
+    fn given_food_near_ant_when_take_turn_then_move_towards_food() {
+        let mut agent = DiffusionAgent::new(&a_wide_view_config());
+
+        let orders = agent.take_turn(
+            a_turn(vec![], vec![pos(5, 2)], vec![pos(5, 5)]),
+            &a_context(),
+        );
+
+        assert_eq!(
+            orders,
+            vec![Order {
+                pos: pos(5, 5),
+                direction: Direction::N,
+            }]
+        );
+    }
+
+    #[test]
+    fn given_larger_map_when_setup_then_take_turn_on_that_map() {
+        let mut agent = DiffusionAgent::new(&a_wide_view_config());
+        agent.setup(&Config {
+            width: 30,
+            height: 40,
+            ..a_wide_view_config()
+        });
+
+        let orders = agent.take_turn(
+            a_turn(vec![], vec![pos(25, 32)], vec![pos(25, 35)]),
+            &a_context(),
+        );
+
+        assert_eq!(
+            orders,
+            vec![Order {
+                pos: pos(25, 35),
+                direction: Direction::N,
+            }]
+        );
+    }
+
+    #[test]
+    fn given_water_around_ant_when_take_turn_then_never_order_into_water() {
+        let mut agent = DiffusionAgent::new(&a_wide_view_config());
+        let water = vec![pos(5, 4), pos(6, 5), pos(4, 5)];
+
+        let orders = agent.take_turn(
+            a_turn(water, vec![pos(5, 2), pos(5, 8)], vec![pos(5, 5)]),
+            &a_context(),
+        );
+
+        assert_eq!(
+            orders,
+            vec![Order {
+                pos: pos(5, 5),
+                direction: Direction::S,
+            }]
+        );
+    }
+}
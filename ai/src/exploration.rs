@@ -0,0 +1,162 @@
+use crate::multi_source_bfs_within;
+use crate::Config;
+use crate::Direction;
+use crate::MoveResolver;
+use crate::Position;
+use crate::TimeBudget;
+use crate::World;
+use std::iter;
+
+#[derive(Debug)]
+pub struct Explorer {
+    search_distance: i32,
+    spread_radius2: i32,
+}
+
+impl Explorer {
+    pub fn new(config: &Config) -> Self {
+        let view_radius = (config.view_radius2 as f64).sqrt() as i32;
+        Self {
+            search_distance: view_radius * 2 + 1,
+            spread_radius2: config.view_radius2,
+        }
+    }
+
+    pub fn staleness(&self, world: &World, pos: Position) -> i32 {
+        match world.last_seen(pos) {
+            Some(turn) => world.turn() - turn,
+            None => world.turn() + 1,
+        }
+    }
+
+    pub fn target(
+        &self,
+        world: &World,
+        ant: Position,
+        claimed: &[Position],
+    ) -> Option<(Position, Direction)> {
+        let geometry = world.geometry();
+        let field = multi_source_bfs_within(world, iter::once(ant), self.search_distance);
+        field
+            .reached()
+            .filter(|&(pos, distance)| {
+                distance > 0
+                    && !claimed
+                        .iter()
+                        .any(|&c| geometry.distance2(c, pos) <= self.spread_radius2)
+            })
+            .map(|(pos, distance)| {
+                let score = i64::from(self.staleness(world, pos)) * 1000 / i64::from(distance + 1);
+                (score, pos)
+            })
+            .filter(|&(score, _)| score > 0)
+            .max_by_key(|&(score, pos)| (score, -pos.y, -pos.x))
+            .and_then(|(_, pos)| field.first_step(pos).map(|d| (pos, d)))
+    }
+
+    pub fn explore(
+        &self,
+        world: &World,
+        moves: &mut MoveResolver,
+        budget: &TimeBudget,
+    ) -> Vec<(Position, Position)> {
+        let mut ants: Vec<Position> = moves.idle_ants().collect();
+        ants.sort_by_key(|a| (a.y, a.x));
+
+        let mut claimed = vec![];
+        let mut explorations = vec![];
+        for ant in ants {
+            if budget.is_exhausted() {
+                break;
+            }
+            if let Some((target, direction)) = self.target(world, ant, &claimed) {
+                if moves.order(ant, direction) {
+                    claimed.push(target);
+                    explorations.push((ant, target));
+                }
+            }
+        }
+        explorations
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::a_config;
+    use crate::test_support::an_empty_turn_info;
+    use crate::test_support::own_ants;
+    use crate::test_support::pos;
+    use crate::TurnInfo;
+    use std::time::Duration;
+
+    fn an_exploration_config() -> Config {
+        Config {
+            width: 40,
+            height: 40,
+            view_radius2: 9,
+            ..a_config()
+        }
+    }
+
+    fn a_turn(ants: Vec<Position>) -> TurnInfo {
+        TurnInfo {
+            ant: own_ants(ants),
+            ..an_empty_turn_info()
+        }
+    }
+
+    #[test]
+    fn given_seen_and_unseen_tiles_when_staleness_then_unseen_is_most_stale() {
+        let mut world = World::new(&an_exploration_config());
+        world.update(1, &a_turn(vec![pos(10, 10)]));
+        world.update(2, &a_turn(vec![pos(30, 30)]));
+        let explorer = Explorer::new(&an_exploration_config());
+
+        assert_eq!(explorer.staleness(&world, pos(30, 30)), 0);
+        assert_eq!(explorer.staleness(&world, pos(10, 10)), 1);
+        assert_eq!(explorer.staleness(&world, pos(20, 20)), 3);
+    }
+
+    #[test]
+    fn given_seen_region_on_one_side_when_target_then_head_towards_unseen_region() {
+        let mut world = World::new(&an_exploration_config());
+        world.update(1, &a_turn(vec![pos(14, 10), pos(17, 10), pos(20, 10)]));
+        world.update(2, &a_turn(vec![pos(10, 10)]));
+        let explorer = Explorer::new(&an_exploration_config());
+
+        let (target, direction) = explorer.target(&world, pos(10, 10), &[]).unwrap();
+
+        assert_ne!(direction, Direction::E);
+        assert_eq!(world.last_seen(target), None);
+    }
+
+    #[test]
+    fn given_several_idle_ants_when_explore_then_spread_targets_apart() {
+        let mut world = World::new(&an_exploration_config());
+        world.update(1, &a_turn(vec![pos(10, 10), pos(11, 10)]));
+        let explorer = Explorer::new(&an_exploration_config());
+        let mut moves = MoveResolver::new(&world);
+
+        let explorations = explorer.explore(&world, &mut moves, &TimeBudget::unlimited());
+
+        assert_eq!(explorations.len(), 2);
+        let (a, b) = (explorations[0].1, explorations[1].1);
+        assert!(world.geometry().distance2(a, b) > 9);
+        assert_eq!(moves.resolve().len(), 2);
+    }
+
+    #[test]
+    fn given_exhausted_budget_when_explore_then_order_nothing() {
+        let mut world = World::new(&an_exploration_config());
+        world.update(1, &a_turn(vec![pos(10, 10), pos(11, 10)]));
+        let explorer = Explorer::new(&an_exploration_config());
+        let mut moves = MoveResolver::new(&world);
+
+        let explorations =
+            explorer.explore(&world, &mut moves, &TimeBudget::new(Duration::from_secs(0)));
+
+        assert!(explorations.is_empty());
+        assert_eq!(moves.idle_ants().count(), 2);
+    }
+}
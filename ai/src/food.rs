@@ -0,0 +1,202 @@
+use crate::multi_source_bfs_within;
+use crate::visibility::circle_offsets;
+use crate::Config;
+use crate::Direction;
+use crate::Matching;
+use crate::MoveResolver;
+use crate::Position;
+use crate::TimeBudget;
+use crate::World;
+
+const SEARCH_DISTANCE: i32 = 30;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FoodAssignment {
+    pub ant: Position,
+    pub food: Position,
+    pub distance: i32,
+    pub direction: Option<Direction>,
+}
+
+#[derive(Debug)]
+pub struct FoodGatherer {
+    gathering_offsets: Vec<(i32, i32)>,
+    matching: Matching,
+}
+
+impl FoodGatherer {
+    pub fn new(config: &Config, matching: Matching) -> Self {
+        Self {
+            gathering_offsets: circle_offsets(config.food_gathering_radius2),
+            matching,
+        }
+    }
+
+    pub fn assign(
+        &self,
+        world: &World,
+        ants: impl IntoIterator<Item = Position>,
+    ) -> Vec<FoodAssignment> {
+        self.assign_within(world, ants, &TimeBudget::unlimited())
+    }
+
+    pub fn gather(
+        &self,
+        world: &World,
+        moves: &mut MoveResolver,
+        budget: &TimeBudget,
+    ) -> Vec<FoodAssignment> {
+        let ants: Vec<Position> = moves.idle_ants().collect();
+        let mut assignments = self.assign_within(world, ants, budget);
+        assignments.sort_by_key(|a| a.distance);
+        for assignment in &assignments {
+            match assignment.direction {
+                Some(direction) => {
+                    moves.order(assignment.ant, direction);
+                }
+                None => {
+                    moves.hold(assignment.ant);
+                }
+            }
+        }
+        assignments
+    }
+
+    fn assign_within(
+        &self,
+        world: &World,
+        ants: impl IntoIterator<Item = Position>,
+        budget: &TimeBudget,
+    ) -> Vec<FoodAssignment> {
+        let ants: Vec<Position> = ants.into_iter().collect();
+        let food = world.food();
+
+        let fields: Vec<_> = food
+            .iter()
+            .take_while(|_| !budget.is_exhausted())
+            .map(|&f| {
+                let gathering_tiles =
+                    self.gathering_offsets
+                        .iter()
+                        .map(move |&(dx, dy)| Position {
+                            x: f.x + dx,
+                            y: f.y + dy,
+                        });
+                multi_source_bfs_within(
+                    world,
+                    gathering_tiles.filter(|&p| !world.is_water(p)),
+                    SEARCH_DISTANCE,
+                )
+            })
+            .collect();
+        let costs: Vec<Vec<Option<i32>>> = fields
+            .iter()
+            .map(|field| ants.iter().map(|&a| field.distance(a)).collect())
+            .collect();
+
+        self.matching
+            .solve(&costs)
+            .into_iter()
+            .map(|(f, a)| FoodAssignment {
+                ant: ants[a],
+                food: food[f],
+                distance: costs[f][a].unwrap_or(0),
+                direction: fields[f].direction_to_origin(ants[a]),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use crate::test_support::a_config;
+    use crate::test_support::an_empty_turn_info;
+    use crate::test_support::own_ants;
+    use crate::test_support::pos;
+    use crate::TurnInfo;
+    use std::time::Duration;
+
+    fn a_world(food: Vec<Position>, ants: Vec<Position>) -> World {
+        test_support::a_world(
+            &a_config(),
+            &TurnInfo {
+                food,
+                ant: own_ants(ants),
+                ..an_empty_turn_info()
+            },
+        )
+    }
+
+    #[test]
+    fn given_food_and_ants_when_assign_then_assign_nearest_ant_to_each_food() {
+        let world = a_world(vec![pos(5, 5)], vec![pos(5, 9), pos(5, 12)]);
+        let gatherer = FoodGatherer::new(&a_config(), Matching::Greedy);
+
+        let assignments = gatherer.assign(&world, world.own_ants().map(|a| a.pos));
+
+        assert_eq!(
+            assignments,
+            vec![FoodAssignment {
+                ant: pos(5, 9),
+                food: pos(5, 5),
+                distance: 3,
+                direction: Some(Direction::N),
+            }]
+        );
+    }
+
+    #[test]
+    fn given_ant_within_gathering_radius_when_assign_then_ant_does_not_need_to_move() {
+        let world = a_world(vec![pos(5, 5)], vec![pos(6, 5)]);
+        let gatherer = FoodGatherer::new(&a_config(), Matching::Greedy);
+
+        let assignments = gatherer.assign(&world, world.own_ants().map(|a| a.pos));
+
+        assert_eq!(assignments[0].distance, 0);
+        assert_eq!(assignments[0].direction, None);
+    }
+
+    #[test]
+    fn given_competing_food_when_assign_optimal_then_minimise_total_distance() {
+        let world = a_world(vec![pos(5, 5), pos(9, 5)], vec![pos(7, 5), pos(3, 5)]);
+        let ants = vec![pos(7, 5), pos(3, 5)];
+
+        let greedy = FoodGatherer::new(&a_config(), Matching::Greedy).assign(&world, ants.clone());
+        let optimal = FoodGatherer::new(&a_config(), Matching::Optimal).assign(&world, ants);
+
+        let total = |a: &[FoodAssignment]| a.iter().map(|a| a.distance).sum::<i32>();
+        assert_eq!(greedy.len(), 2);
+        assert_eq!(optimal.len(), 2);
+        assert!(total(&optimal) <= total(&greedy));
+        assert_eq!(total(&optimal), 2);
+    }
+
+    #[test]
+    fn given_more_ants_than_food_when_gather_then_order_only_assigned_ants() {
+        let world = a_world(vec![pos(5, 5)], vec![pos(5, 8), pos(5, 12)]);
+        let gatherer = FoodGatherer::new(&a_config(), Matching::Optimal);
+        let mut moves = MoveResolver::new(&world);
+
+        gatherer.gather(&world, &mut moves, &TimeBudget::unlimited());
+
+        let orders = moves.resolve();
+        assert_eq!(orders.len(), 1);
+        assert_eq!(orders[0].pos, pos(5, 8));
+        assert_eq!(orders[0].direction, Direction::N);
+    }
+
+    #[test]
+    fn given_exhausted_budget_when_gather_then_order_nothing() {
+        let world = a_world(vec![pos(5, 5)], vec![pos(5, 8)]);
+        let gatherer = FoodGatherer::new(&a_config(), Matching::Optimal);
+        let mut moves = MoveResolver::new(&world);
+
+        let assignments =
+            gatherer.gather(&world, &mut moves, &TimeBudget::new(Duration::from_secs(0)));
+
+        assert!(assignments.is_empty());
+        assert!(moves.is_idle(pos(5, 8)));
+    }
+}
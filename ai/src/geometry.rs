@@ -0,0 +1,177 @@
+use crate::Config;
+use crate::Direction;
+use crate::Position;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Geometry {
+    width: i32,
+    height: i32,
+}
+
+impl Geometry {
+    pub fn new(width: i32, height: i32) -> Self {
+        Self { width, height }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(config.width, config.height)
+    }
+
+    pub fn width(&self) -> i32 {
+        self.width
+    }
+
+    pub fn height(&self) -> i32 {
+        self.height
+    }
+
+    pub fn size(&self) -> usize {
+        (self.width * self.height) as usize
+    }
+
+    pub fn contains(&self, pos: Position) -> bool {
+        pos.x >= 0 && pos.x < self.width && pos.y >= 0 && pos.y < self.height
+    }
+
+    pub fn wrap(&self, pos: Position) -> Position {
+        Position {
+            x: pos.x.rem_euclid(self.width),
+            y: pos.y.rem_euclid(self.height),
+        }
+    }
+
+    pub fn index(&self, pos: Position) -> usize {
+        let pos = self.wrap(pos);
+        (pos.y * self.width + pos.x) as usize
+    }
+
+    pub fn position(&self, index: usize) -> Position {
+        let index = index as i32;
+        Position {
+            x: index % self.width,
+            y: index / self.width,
+        }
+    }
+
+    pub fn step(&self, pos: Position, direction: Direction) -> Position {
+        self.wrap(pos.step(direction))
+    }
+
+    pub fn neighbours(&self, pos: Position) -> [Position; 4] {
+        Direction::ALL.map(|d| self.step(pos, d))
+    }
+
+    pub fn delta(&self, from: Position, to: Position) -> (i32, i32) {
+        (
+            wrapped_delta(from.x, to.x, self.width),
+            wrapped_delta(from.y, to.y, self.height),
+        )
+    }
+
+    pub fn manhattan_distance(&self, a: Position, b: Position) -> i32 {
+        let (dx, dy) = self.delta(a, b);
+        dx.abs() + dy.abs()
+    }
+
+    pub fn distance2(&self, a: Position, b: Position) -> i32 {
+        let (dx, dy) = self.delta(a, b);
+        dx * dx + dy * dy
+    }
+
+    pub fn directions(&self, from: Position, to: Position) -> Vec<Direction> {
+        let (dx, dy) = self.delta(from, to);
+        let mut directions = vec![];
+        if dy < 0 {
+            directions.push(Direction::N);
+        } else if dy > 0 {
+            directions.push(Direction::S);
+        }
+        if dx > 0 {
+            directions.push(Direction::E);
+        } else if dx < 0 {
+            directions.push(Direction::W);
+        }
+        directions
+    }
+}
+
+fn wrapped_delta(from: i32, to: i32, size: i32) -> i32 {
+    let delta = (to - from).rem_euclid(size);
+    if delta * 2 > size {
+        delta - size
+    } else {
+        delta
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pos;
+
+    #[test]
+    fn given_position_at_edge_when_step_off_board_then_wrap_around() {
+        let geometry = Geometry::new(20, 30);
+
+        assert_eq!(geometry.step(pos(0, 0), Direction::N), pos(0, 29));
+        assert_eq!(geometry.step(pos(0, 0), Direction::W), pos(19, 0));
+        assert_eq!(geometry.step(pos(19, 29), Direction::S), pos(19, 0));
+        assert_eq!(geometry.step(pos(19, 29), Direction::E), pos(0, 29));
+        assert_eq!(geometry.step(pos(5, 5), Direction::N), pos(5, 4));
+    }
+
+    #[test]
+    fn when_neighbours_then_return_wrapped_neighbour_in_every_direction() {
+        let geometry = Geometry::new(20, 30);
+
+        let neighbours = geometry.neighbours(pos(0, 0));
+
+        assert_eq!(neighbours, [pos(0, 29), pos(1, 0), pos(0, 1), pos(19, 0)]);
+    }
+
+    #[test]
+    fn given_positions_across_edge_when_distance_then_use_shortest_wrapped_distance() {
+        let geometry = Geometry::new(20, 30);
+
+        assert_eq!(geometry.manhattan_distance(pos(1, 1), pos(19, 29)), 4);
+        assert_eq!(geometry.distance2(pos(1, 1), pos(19, 29)), 8);
+        assert_eq!(geometry.manhattan_distance(pos(2, 3), pos(5, 7)), 7);
+        assert_eq!(geometry.distance2(pos(2, 3), pos(5, 7)), 25);
+    }
+
+    #[test]
+    fn given_target_across_edge_when_directions_then_point_across_edge() {
+        let geometry = Geometry::new(20, 30);
+
+        let directions = geometry.directions(pos(1, 1), pos(19, 29));
+
+        assert_matches!(directions.as_slice(), [Direction::N, Direction::W]);
+    }
+
+    #[test]
+    fn given_same_position_when_directions_then_no_direction() {
+        let geometry = Geometry::new(20, 30);
+
+        let directions = geometry.directions(pos(4, 4), pos(4, 4));
+
+        assert!(directions.is_empty());
+    }
+
+    #[test]
+    fn when_contains_then_only_positions_on_board() {
+        let geometry = Geometry::new(20, 30);
+
+        assert!(geometry.contains(pos(19, 29)));
+        assert!(!geometry.contains(pos(20, 0)));
+        assert!(!geometry.contains(pos(0, -1)));
+    }
+
+    #[test]
+    fn when_index_then_position_is_inverse() {
+        let geometry = Geometry::new(20, 30);
+
+        let index = geometry.index(pos(7, 11));
+
+        assert_eq!(geometry.position(index), pos(7, 11));
+    }
+}
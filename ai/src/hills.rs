@@ -0,0 +1,200 @@
+use crate::MoveResolver;
+use crate::PathCache;
+use crate::PlayerEntity;
+use crate::Position;
+use crate::World;
+
+#[derive(Debug)]
+pub struct HillRaider {
+    share: f64,
+    targets: Vec<PlayerEntity>,
+    razed: Vec<PlayerEntity>,
+    paths: PathCache,
+}
+
+impl HillRaider {
+    pub fn new(share: f64) -> Self {
+        Self {
+            share: share.clamp(0.0, 1.0),
+            targets: vec![],
+            razed: vec![],
+            paths: PathCache::new(),
+        }
+    }
+
+    pub fn update(&mut self, world: &World) -> Vec<PlayerEntity> {
+        let hills: Vec<PlayerEntity> = world.enemy_hills().copied().collect();
+        let (remaining, razed): (Vec<PlayerEntity>, Vec<PlayerEntity>) =
+            self.targets.drain(..).partition(|h| hills.contains(h));
+        self.targets = remaining;
+        for hill in hills {
+            if !self.targets.contains(&hill) {
+                self.targets.push(hill);
+            }
+        }
+        self.razed.extend(razed.iter().copied());
+        razed
+    }
+
+    pub fn targets(&self) -> &[PlayerEntity] {
+        &self.targets
+    }
+
+    pub fn razed(&self) -> &[PlayerEntity] {
+        &self.razed
+    }
+
+    pub fn raid(&mut self, world: &World, moves: &mut MoveResolver) -> Vec<Position> {
+        let raiders = (world.own_ants().count() as f64 * self.share).ceil() as usize;
+        let geometry = world.geometry();
+        let targets: Vec<Position> = self.targets.iter().map(|h| h.pos).collect();
+
+        let mut candidates: Vec<(bool, i32, Position, Position)> = moves
+            .idle_ants()
+            .filter_map(|ant| {
+                let committed = self.paths.target(ant).filter(|t| targets.contains(t));
+                let target = committed.or_else(|| {
+                    targets
+                        .iter()
+                        .copied()
+                        .min_by_key(|&t| (geometry.manhattan_distance(ant, t), t.y, t.x))
+                })?;
+                let distance = geometry.manhattan_distance(ant, target);
+                Some((committed.is_none(), distance, ant, target))
+            })
+            .collect();
+        candidates.sort_by_key(|&(fresh, d, a, _)| (fresh, d, a.y, a.x));
+
+        let mut dispatched = vec![];
+        let mut missions = vec![];
+        for (_, _, ant, target) in candidates.into_iter().take(raiders) {
+            if let Some(direction) = self.paths.next_step(world, ant, target) {
+                if moves.order(ant, direction) {
+                    dispatched.push(ant);
+                    missions.push(geometry.step(ant, direction));
+                }
+            }
+        }
+        self.paths.retain_ants(&missions);
+        dispatched
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::a_config;
+    use crate::test_support::an_empty_turn_info;
+    use crate::test_support::entity;
+    use crate::Config;
+    use crate::Direction;
+    use crate::TurnInfo;
+
+    fn a_world() -> World {
+        World::new(&Config {
+            width: 30,
+            height: 30,
+            ..a_config()
+        })
+    }
+
+    fn a_turn(ant_hill: Vec<PlayerEntity>, ant: Vec<PlayerEntity>) -> TurnInfo {
+        TurnInfo {
+            ant_hill,
+            ant,
+            ..an_empty_turn_info()
+        }
+    }
+
+    #[test]
+    fn given_enemy_hill_when_raid_then_dispatch_share_of_nearest_ants() {
+        let mut world = a_world();
+        world.update(
+            1,
+            &a_turn(
+                vec![entity(1, 10, 10)],
+                vec![
+                    entity(0, 10, 14),
+                    entity(0, 10, 20),
+                    entity(0, 2, 2),
+                    entity(0, 25, 25),
+                ],
+            ),
+        );
+        let mut raider = HillRaider::new(0.5);
+        raider.update(&world);
+        let mut moves = MoveResolver::new(&world);
+
+        let dispatched = raider.raid(&world, &mut moves);
+
+        assert_eq!(
+            dispatched,
+            vec![Position { x: 10, y: 14 }, Position { x: 10, y: 20 }]
+        );
+        let orders = moves.resolve();
+        assert_eq!(orders.len(), 2);
+        assert!(orders
+            .iter()
+            .any(|o| o.pos == Position { x: 10, y: 14 } && o.direction == Direction::N));
+    }
+
+    #[test]
+    fn given_no_enemy_hills_when_raid_then_dispatch_nobody() {
+        let mut world = a_world();
+        world.update(1, &a_turn(vec![entity(0, 10, 10)], vec![entity(0, 10, 14)]));
+        let mut raider = HillRaider::new(1.0);
+        raider.update(&world);
+        let mut moves = MoveResolver::new(&world);
+
+        let dispatched = raider.raid(&world, &mut moves);
+
+        assert!(dispatched.is_empty());
+    }
+
+    #[test]
+    fn given_dispatched_raider_when_raid_next_turn_then_keep_raider_on_its_path() {
+        let mut world = a_world();
+        world.update(
+            1,
+            &a_turn(
+                vec![entity(1, 10, 10)],
+                vec![entity(0, 10, 14), entity(0, 25, 25)],
+            ),
+        );
+        let mut raider = HillRaider::new(0.5);
+        raider.update(&world);
+        raider.raid(&world, &mut MoveResolver::new(&world));
+        world.update(
+            2,
+            &a_turn(
+                vec![entity(1, 10, 10)],
+                vec![entity(0, 10, 13), entity(0, 11, 11)],
+            ),
+        );
+        raider.update(&world);
+        let mut moves = MoveResolver::new(&world);
+
+        let dispatched = raider.raid(&world, &mut moves);
+
+        assert_eq!(dispatched, vec![Position { x: 10, y: 13 }]);
+        assert_eq!(
+            raider.paths.target(Position { x: 10, y: 12 }),
+            Some(Position { x: 10, y: 10 })
+        );
+    }
+
+    #[test]
+    fn given_hill_razed_when_update_then_report_razed_hill_and_stop_targeting_it() {
+        let mut world = a_world();
+        world.update(1, &a_turn(vec![entity(1, 10, 10)], vec![entity(0, 10, 11)]));
+        let mut raider = HillRaider::new(1.0);
+        raider.update(&world);
+        world.update(2, &a_turn(vec![], vec![entity(0, 10, 10)]));
+
+        let razed = raider.update(&world);
+
+        assert_eq!(razed, vec![entity(1, 10, 10)]);
+        assert_eq!(raider.razed(), &[entity(1, 10, 10)]);
+        assert!(raider.targets().is_empty());
+    }
+}
@@ -1,6 +1,57 @@
-use rand::Rng;
+mod anytime;
+mod battle;
+mod combat;
+mod defence;
+mod diffusion;
+mod exploration;
+mod food;
+mod geometry;
+mod hills;
+mod map;
+mod matching;
+mod moves;
+mod pathfinding;
+#[cfg(test)]
+mod test_support;
+mod visibility;
+mod world;
 
-#[derive(Debug)]
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+pub use anytime::OrderDraft;
+pub use anytime::TimeBudget;
+pub use battle::BattlePlan;
+pub use battle::BattlePlanner;
+pub use battle::Skirmish;
+pub use battle::Tactic;
+pub use combat::CombatSimulator;
+pub use defence::HillDefender;
+pub use defence::Threat;
+pub use diffusion::DiffusionAgent;
+pub use diffusion::ScentMap;
+pub use exploration::Explorer;
+pub use food::FoodAssignment;
+pub use food::FoodGatherer;
+pub use geometry::Geometry;
+pub use hills::HillRaider;
+pub use map::Map;
+pub use map::MapError;
+pub use matching::greedy_matching;
+pub use matching::optimal_matching;
+pub use matching::Matching;
+pub use moves::MoveResolver;
+pub use pathfinding::astar;
+pub use pathfinding::bfs;
+pub use pathfinding::multi_source_bfs;
+pub use pathfinding::multi_source_bfs_within;
+pub use pathfinding::DistanceField;
+pub use pathfinding::PathCache;
+pub use visibility::Visibility;
+pub use world::World;
+
+#[derive(Debug, Clone)]
 pub struct Config {
     pub load_time: i32,
     pub turn_time: i32,
@@ -11,17 +62,53 @@ pub struct Config {
     pub attack_radius2: i32,
     pub food_gathering_radius2: i32,
     pub player_seed: i64,
+    pub players: Option<i32>,
+    pub cutoff_turn: Option<i32>,
+    pub cutoff_percent: Option<f64>,
+    pub extra: HashMap<String, String>,
 }
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub struct PlayerEntity {
     pub id: i32,
     pub pos: Position,
 }
-#[derive(Debug, Copy, Clone, PartialEq)]
+
+impl PlayerEntity {
+    pub fn is_own(&self) -> bool {
+        self.id == 0
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct Position {
     pub x: i32,
     pub y: i32,
 }
+
+impl Position {
+    pub(crate) fn step(self, direction: Direction) -> Position {
+        match direction {
+            Direction::N => Position {
+                x: self.x,
+                y: self.y - 1,
+            },
+            Direction::E => Position {
+                x: self.x + 1,
+                y: self.y,
+            },
+            Direction::S => Position {
+                x: self.x,
+                y: self.y + 1,
+            },
+            Direction::W => Position {
+                x: self.x - 1,
+                y: self.y,
+            },
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct TurnInfo {
     pub water: Vec<Position>, // Sent once
@@ -30,12 +117,20 @@ pub struct TurnInfo {
     pub ant: Vec<PlayerEntity>,
     pub dead_ant: Vec<PlayerEntity>,
 }
+#[derive(Debug, Clone)]
+pub struct TurnContext {
+    pub turn: i32,
+    pub turns: i32,
+    pub deadline: Option<Instant>,
+    pub draft: OrderDraft,
+}
+
 #[derive(Debug)]
 pub struct EndInfo {
     pub scores: Vec<i32>,
     pub turn_info: TurnInfo,
 }
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Direction {
     N,
     E,
@@ -43,7 +138,20 @@ pub enum Direction {
     W,
 }
 
-#[derive(Debug, Copy, Clone)]
+impl Direction {
+    pub const ALL: [Direction; 4] = [Direction::N, Direction::E, Direction::S, Direction::W];
+
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::N => Direction::S,
+            Direction::E => Direction::W,
+            Direction::S => Direction::N,
+            Direction::W => Direction::E,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub struct Order {
     pub pos: Position,
     pub direction: Direction,
@@ -59,32 +167,85 @@ impl Order {
 }
 
 pub trait TurnTaker {
-    fn take_turn(&mut self, turn_info: TurnInfo) -> Vec<Order>;
+    fn setup(&mut self, _config: &Config) {}
+    fn take_turn(&mut self, turn_info: TurnInfo, context: &TurnContext) -> Vec<Order>;
     fn end(&mut self, end_info: EndInfo);
 }
 
-pub struct Agent {}
+const RAID_SHARE: f64 = 0.2;
+const DEFENCE_RADIUS: i32 = 12;
+const TURN_MARGIN: Duration = Duration::from_millis(100);
+
+#[derive(Debug)]
+struct Strategy {
+    world: World,
+    defender: HillDefender,
+    battle: BattlePlanner,
+    food: FoodGatherer,
+    raider: HillRaider,
+    explorer: Explorer,
+}
+
+impl Strategy {
+    fn new(config: &Config) -> Self {
+        Self {
+            world: World::new(config),
+            defender: HillDefender::new(DEFENCE_RADIUS),
+            battle: BattlePlanner::new(config),
+            food: FoodGatherer::new(config, Matching::Optimal),
+            raider: HillRaider::new(RAID_SHARE),
+            explorer: Explorer::new(config),
+        }
+    }
+
+    fn take_turn(&mut self, turn_info: &TurnInfo, context: &TurnContext) -> Vec<Order> {
+        let budget = TimeBudget::from_context(context, TURN_MARGIN);
+        let Strategy {
+            world,
+            defender,
+            battle,
+            food,
+            raider,
+            explorer,
+        } = self;
+        world.update(context.turn, turn_info);
+        raider.update(world);
+
+        let mut moves = MoveResolver::new(world);
+        defender.defend(world, &mut moves);
+        context.draft.publish(moves.snapshot());
+        battle.fight(world, &mut moves, &budget);
+        context.draft.publish(moves.snapshot());
+        if !budget.is_exhausted() {
+            food.gather(world, &mut moves, &budget);
+            context.draft.publish(moves.snapshot());
+        }
+        if !budget.is_exhausted() {
+            raider.raid(world, &mut moves);
+            context.draft.publish(moves.snapshot());
+        }
+        if !budget.is_exhausted() {
+            explorer.explore(world, &mut moves, &budget);
+        }
+        moves.resolve()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Agent {
+    strategy: Option<Strategy>,
+}
 
 impl TurnTaker for Agent {
-    fn take_turn(&mut self, turn_info: TurnInfo) -> Vec<Order> {
-        let mut rng = rand::thread_rng();
-        turn_info
-            .ant
-            .iter()
-            .filter(|a| a.id == 0)
-            .map(|a| {
-                let direction = match rng.gen_range(0, 3) {
-                    0 => Direction::N,
-                    1 => Direction::E,
-                    2 => Direction::S,
-                    _ => Direction::W,
-                };
-                Order {
-                    pos: a.pos,
-                    direction,
-                }
-            })
-            .collect()
+    fn setup(&mut self, config: &Config) {
+        self.strategy = Some(Strategy::new(config));
+    }
+
+    fn take_turn(&mut self, turn_info: TurnInfo, context: &TurnContext) -> Vec<Order> {
+        match &mut self.strategy {
+            Some(strategy) => strategy.take_turn(&turn_info, context),
+            None => vec![],
+        }
     }
 
     fn end(&mut self, _end_info: EndInfo) {}
@@ -97,13 +258,15 @@ extern crate assert_matches;
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::test_support::a_config;
+    use crate::test_support::a_context;
 
     #[test]
-    fn when_make_turn_then_return_order_for_every_own_ant() {
-        let mut agent = Agent {};
+    fn given_no_setup_when_take_turn_then_hold_every_ant() {
+        let mut agent = Agent::default();
         let turn_info = TurnInfo {
             water: vec![],
-            food: vec![],
+            food: vec![Position { x: 2, y: 2 }],
             ant_hill: vec![],
             ant: vec![
                 PlayerEntity {
@@ -122,13 +285,47 @@ mod tests {
             dead_ant: vec![],
         };
 
-        let mut orders = agent.take_turn(turn_info).into_iter();
-        assert_matches!(
-            orders.next(),
-            Some(Order {
+        let orders = agent.take_turn(turn_info, &a_context());
+
+        assert!(orders.is_empty());
+    }
+
+    #[test]
+    fn given_setup_when_take_turn_then_never_order_two_ants_onto_same_tile() {
+        let mut agent = Agent::default();
+        agent.setup(&a_config());
+        let ants = [
+            Position { x: 2, y: 1 },
+            Position { x: 3, y: 1 },
+            Position { x: 2, y: 2 },
+            Position { x: 3, y: 2 },
+        ];
+        let turn_info = TurnInfo {
+            water: vec![Position { x: 2, y: 0 }],
+            food: vec![Position { x: 5, y: 1 }],
+            ant_hill: vec![PlayerEntity {
+                id: 0,
                 pos: Position { x: 2, y: 1 },
-                ..
-            })
-        );
+            }],
+            ant: ants
+                .iter()
+                .map(|&pos| PlayerEntity { id: 0, pos })
+                .collect(),
+            dead_ant: vec![],
+        };
+
+        let orders = agent.take_turn(turn_info, &a_context());
+
+        let geometry = Geometry::new(20, 20);
+        let mut occupied: Vec<Position> = ants
+            .iter()
+            .copied()
+            .filter(|a| orders.iter().all(|o| o.pos != *a))
+            .collect();
+        occupied.extend(orders.iter().map(|o| geometry.step(o.pos, o.direction)));
+        occupied.sort_by_key(|p| (p.y, p.x));
+        occupied.dedup();
+        assert_eq!(occupied.len(), ants.len());
+        assert!(!orders.is_empty());
     }
 }
@@ -0,0 +1,422 @@
+use crate::Geometry;
+use crate::PlayerEntity;
+use crate::Position;
+use crate::TurnInfo;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[derive(Debug)]
+pub enum MapError {
+    Io(io::Error),
+    MissingParameter(&'static str),
+    InvalidParameter {
+        line: usize,
+        text: String,
+    },
+    InvalidTile {
+        line: usize,
+        column: usize,
+        tile: char,
+    },
+    WrongRowLength {
+        line: usize,
+        expected: i32,
+        found: usize,
+    },
+    WrongRowCount {
+        expected: i32,
+        found: usize,
+    },
+    MissingHill {
+        player: i32,
+    },
+    TooManyPlayers {
+        players: usize,
+    },
+}
+
+impl fmt::Display for MapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MapError::Io(error) => write!(f, "cannot read map: {}", error),
+            MapError::MissingParameter(parameter) => {
+                write!(f, "missing map parameter '{}'", parameter)
+            }
+            MapError::InvalidParameter { line, text } => {
+                write!(f, "invalid map parameter at line {}: '{}'", line, text)
+            }
+            MapError::InvalidTile { line, column, tile } => write!(
+                f,
+                "invalid tile '{}' at line {}, column {}",
+                tile, line, column
+            ),
+            MapError::WrongRowLength {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row at line {} has {} columns, expected {}",
+                line, found, expected
+            ),
+            MapError::WrongRowCount { expected, found } => {
+                write!(f, "map has {} rows, expected {}", found, expected)
+            }
+            MapError::MissingHill { player } => write!(f, "player {} has no hill", player),
+            MapError::TooManyPlayers { players } => write!(
+                f,
+                "map has {} players, at most {} are supported",
+                players,
+                Map::MAX_PLAYERS
+            ),
+        }
+    }
+}
+
+impl std::error::Error for MapError {}
+
+impl From<io::Error> for MapError {
+    fn from(error: io::Error) -> Self {
+        MapError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Map {
+    pub width: i32,
+    pub height: i32,
+    pub water: Vec<Position>,
+    pub food: Vec<Position>,
+    pub hills: Vec<PlayerEntity>,
+    pub ants: Vec<PlayerEntity>,
+}
+
+impl Map {
+    pub const MAX_PLAYERS: usize = 10;
+
+    pub fn new(width: i32, height: i32) -> Self {
+        Self {
+            width,
+            height,
+            water: vec![],
+            food: vec![],
+            hills: vec![],
+            ants: vec![],
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MapError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), MapError> {
+        let players = self.players();
+        if players > Self::MAX_PLAYERS {
+            return Err(MapError::TooManyPlayers { players });
+        }
+        Ok(fs::write(path, self.to_string())?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, MapError> {
+        let mut rows: Option<i32> = None;
+        let mut cols: Option<i32> = None;
+        let mut players: Option<i32> = None;
+        let mut tiles: Vec<(usize, &str)> = vec![];
+
+        for (i, line) in text.lines().enumerate() {
+            let mut type_value = line.trim_end().splitn(2, ' ');
+            let (parameter_type, value) = (type_value.next(), type_value.next());
+            let parameter = match parameter_type {
+                Some("m") => {
+                    tiles.push((i + 1, value.unwrap_or("")));
+                    continue;
+                }
+                Some("rows") => &mut rows,
+                Some("cols") => &mut cols,
+                Some("players") => &mut players,
+                _ => continue,
+            };
+            let value = value.and_then(|v| v.trim().parse().ok()).filter(|&v| v > 0);
+            *parameter = Some(value.ok_or_else(|| MapError::InvalidParameter {
+                line: i + 1,
+                text: line.to_string(),
+            })?);
+        }
+
+        let height = rows.ok_or(MapError::MissingParameter("rows"))?;
+        let width = cols.ok_or(MapError::MissingParameter("cols"))?;
+        let players = players.ok_or(MapError::MissingParameter("players"))?;
+        if players as usize > Self::MAX_PLAYERS {
+            return Err(MapError::TooManyPlayers {
+                players: players as usize,
+            });
+        }
+        if tiles.len() != height as usize {
+            return Err(MapError::WrongRowCount {
+                expected: height,
+                found: tiles.len(),
+            });
+        }
+
+        let mut map = Map::new(width, height);
+        for (y, (line, row)) in tiles.into_iter().enumerate() {
+            if row.chars().count() != width as usize {
+                return Err(MapError::WrongRowLength {
+                    line,
+                    expected: width,
+                    found: row.chars().count(),
+                });
+            }
+            for (x, tile) in row.chars().enumerate() {
+                let pos = Position {
+                    x: x as i32,
+                    y: y as i32,
+                };
+                let invalid = || MapError::InvalidTile {
+                    line,
+                    column: x,
+                    tile,
+                };
+                let owner = |first: char| {
+                    let id = tile as i32 - first as i32;
+                    if id < players {
+                        Ok(PlayerEntity { id, pos })
+                    } else {
+                        Err(invalid())
+                    }
+                };
+                match tile {
+                    '.' => (),
+                    '%' => map.water.push(pos),
+                    '*' => map.food.push(pos),
+                    '0'..='9' => map.hills.push(owner('0')?),
+                    'a'..='j' => map.ants.push(owner('a')?),
+                    'A'..='J' => {
+                        let ant = owner('A')?;
+                        map.hills.push(ant);
+                        map.ants.push(ant);
+                    }
+                    _ => return Err(invalid()),
+                }
+            }
+        }
+
+        if let Some(player) = (0..players).find(|&p| map.hills.iter().all(|h| h.id != p)) {
+            return Err(MapError::MissingHill { player });
+        }
+        Ok(map)
+    }
+
+    pub fn players(&self) -> usize {
+        self.hills
+            .iter()
+            .chain(self.ants.iter())
+            .map(|e| e.id as usize + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    pub fn turn_info(&self) -> TurnInfo {
+        TurnInfo {
+            water: self.water.clone(),
+            food: self.food.clone(),
+            ant_hill: self.hills.clone(),
+            ant: self.ants.clone(),
+            dead_ant: vec![],
+        }
+    }
+}
+
+impl fmt::Display for Map {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let geometry = Geometry::new(self.width, self.height);
+        let mut tiles = vec!['.'; geometry.size()];
+        let mut set = |pos: Position, tile: char| tiles[geometry.index(pos)] = tile;
+        let symbol = |first: char, id: i32| match id as usize {
+            id if id < Self::MAX_PLAYERS => (first as u8 + id as u8) as char,
+            _ => '?',
+        };
+        self.water.iter().for_each(|&pos| set(pos, '%'));
+        self.food.iter().for_each(|&pos| set(pos, '*'));
+        self.hills
+            .iter()
+            .for_each(|h| set(h.pos, symbol('0', h.id)));
+        for ant in &self.ants {
+            if self.hills.contains(ant) {
+                set(ant.pos, symbol('A', ant.id));
+            } else {
+                set(ant.pos, symbol('a', ant.id));
+            }
+        }
+
+        writeln!(f, "rows {}", self.height)?;
+        writeln!(f, "cols {}", self.width)?;
+        writeln!(f, "players {}", self.players())?;
+        for row in tiles.chunks(self.width.max(1) as usize) {
+            writeln!(f, "m {}", row.iter().collect::<String>())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::a_config;
+    use crate::test_support::entity;
+    use crate::test_support::pos;
+    use crate::Config;
+    use crate::World;
+
+    const MAP: &str = "rows 4\n\
+        cols 6\n\
+        players 2\n\
+        m ..%...\n\
+        m .0.*a.\n\
+        m ...B..\n\
+        m %.....\n";
+
+    #[test]
+    fn given_map_text_when_parse_then_return_map() {
+        let map = Map::parse(MAP).unwrap();
+
+        assert_eq!(map.width, 6);
+        assert_eq!(map.height, 4);
+        assert_eq!(map.water, vec![pos(2, 0), pos(0, 3)]);
+        assert_eq!(map.food, vec![pos(3, 1)]);
+        assert_eq!(map.hills, vec![entity(0, 1, 1), entity(1, 3, 2)]);
+        assert_eq!(map.ants, vec![entity(0, 4, 1), entity(1, 3, 2)]);
+        assert_eq!(map.players(), 2);
+    }
+
+    #[test]
+    fn given_map_when_written_and_parsed_then_return_same_map() {
+        let map = Map::parse(MAP).unwrap();
+
+        let text = map.to_string();
+
+        assert_eq!(text.replace(' ', ""), MAP.replace(' ', ""));
+        assert_eq!(Map::parse(&text).unwrap(), map);
+    }
+
+    #[test]
+    fn given_saved_map_when_load_then_return_same_map() {
+        let map = Map::parse(MAP).unwrap();
+        let path = std::env::temp_dir().join(format!("ants-map-{}.map", std::process::id()));
+
+        map.save(&path).unwrap();
+        let loaded = Map::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(loaded.unwrap(), map);
+        assert_matches!(Map::load(&path), Err(MapError::Io(_)));
+    }
+
+    #[test]
+    fn given_map_when_turn_info_then_world_knows_whole_map() {
+        let map = Map::parse(MAP).unwrap();
+        let mut world = World::new(&Config {
+            width: map.width,
+            height: map.height,
+            ..a_config()
+        });
+
+        world.update(1, &map.turn_info());
+
+        assert!(world.is_water(pos(0, 3)));
+        assert_eq!(world.enemy_hills().count(), 1);
+    }
+
+    #[test]
+    fn given_missing_parameter_when_parse_then_return_error() {
+        let result = Map::parse(&MAP.replace("players 2", ""));
+
+        assert_matches!(result, Err(MapError::MissingParameter("players")));
+    }
+
+    #[test]
+    fn given_invalid_parameter_when_parse_then_return_error() {
+        let result = Map::parse(&MAP.replace("cols 6", "cols x"));
+
+        assert_matches!(result, Err(MapError::InvalidParameter { line: 2, .. }));
+    }
+
+    #[test]
+    fn given_short_row_when_parse_then_return_error() {
+        let result = Map::parse(&MAP.replace("m ...B..", "m ...B."));
+
+        assert_matches!(
+            result,
+            Err(MapError::WrongRowLength {
+                line: 6,
+                expected: 6,
+                found: 5
+            })
+        );
+    }
+
+    #[test]
+    fn given_missing_row_when_parse_then_return_error() {
+        let result = Map::parse(&MAP.replace("m %.....\n", ""));
+
+        assert_matches!(
+            result,
+            Err(MapError::WrongRowCount {
+                expected: 4,
+                found: 3
+            })
+        );
+    }
+
+    #[test]
+    fn given_unknown_tile_or_player_when_parse_then_return_error() {
+        let unknown = Map::parse(&MAP.replace("m ..%...", "m ..%..x"));
+        let player = Map::parse(&MAP.replace("m ..%...", "m ..%..c"));
+
+        assert_matches!(
+            unknown,
+            Err(MapError::InvalidTile {
+                line: 4,
+                column: 5,
+                tile: 'x'
+            })
+        );
+        assert_matches!(player, Err(MapError::InvalidTile { tile: 'c', .. }));
+    }
+
+    #[test]
+    fn given_tenth_player_when_written_and_parsed_then_return_same_map() {
+        let mut map = Map::new(12, 1);
+        map.hills = (0..10).map(|id| entity(id, id, 0)).collect();
+        map.ants = vec![entity(9, 9, 0), entity(9, 10, 0)];
+
+        let text = map.to_string();
+
+        assert!(text.contains("players 10\n"));
+        assert!(text.contains("m 012345678Jj.\n"));
+        assert_eq!(Map::parse(&text).unwrap(), map);
+    }
+
+    #[test]
+    fn given_more_than_ten_players_when_parse_or_save_then_return_error() {
+        let mut map = Map::new(12, 1);
+        map.hills = (0..11).map(|id| entity(id, id, 0)).collect();
+        let path = std::env::temp_dir().join(format!("ants-map-11-{}.map", std::process::id()));
+
+        let parsed = Map::parse(&MAP.replace("players 2", "players 11"));
+        let saved = map.save(&path);
+
+        assert_matches!(parsed, Err(MapError::TooManyPlayers { players: 11 }));
+        assert_matches!(saved, Err(MapError::TooManyPlayers { players: 11 }));
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn given_player_without_hill_when_parse_then_return_error() {
+        let result = Map::parse(&MAP.replace("B", "b"));
+
+        assert_matches!(result, Err(MapError::MissingHill { player: 1 }));
+    }
+}
@@ -0,0 +1,182 @@
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Matching {
+    Greedy,
+    Optimal,
+}
+
+impl Matching {
+    pub fn solve(self, costs: &[Vec<Option<i32>>]) -> Vec<(usize, usize)> {
+        match self {
+            Matching::Greedy => greedy_matching(costs),
+            Matching::Optimal => optimal_matching(costs),
+        }
+    }
+}
+
+pub fn greedy_matching(costs: &[Vec<Option<i32>>]) -> Vec<(usize, usize)> {
+    let mut candidates: Vec<(i32, usize, usize)> = costs
+        .iter()
+        .enumerate()
+        .flat_map(|(row, r)| {
+            r.iter()
+                .enumerate()
+                .filter_map(move |(col, c)| c.map(|c| (c, row, col)))
+        })
+        .collect();
+    candidates.sort();
+
+    let columns = costs.iter().map(|r| r.len()).max().unwrap_or(0);
+    let mut row_used = vec![false; costs.len()];
+    let mut col_used = vec![false; columns];
+    let mut matches = vec![];
+    for (_, row, col) in candidates {
+        if !row_used[row] && !col_used[col] {
+            row_used[row] = true;
+            col_used[col] = true;
+            matches.push((row, col));
+        }
+    }
+    matches
+}
+
+pub fn optimal_matching(costs: &[Vec<Option<i32>>]) -> Vec<(usize, usize)> {
+    let rows = costs.len();
+    let columns = costs.iter().map(|r| r.len()).max().unwrap_or(0);
+    if rows == 0 || columns == 0 {
+        return vec![];
+    }
+
+    let cost = |row: usize, col: usize| costs[row].get(col).copied().flatten();
+    let matches = if rows <= columns {
+        hungarian(rows, columns, cost)
+    } else {
+        hungarian(columns, rows, |r, c| cost(c, r))
+            .into_iter()
+            .map(|(col, row)| (row, col))
+            .collect()
+    };
+    let mut matches: Vec<(usize, usize)> = matches
+        .into_iter()
+        .filter(|&(row, col)| cost(row, col).is_some())
+        .collect();
+    matches.sort();
+    matches
+}
+
+const UNMATCHABLE: i64 = 1_000_000_000;
+
+// Kuhn-Munkres with potentials, requires rows <= columns.
+fn hungarian(
+    rows: usize,
+    columns: usize,
+    cost: impl Fn(usize, usize) -> Option<i32>,
+) -> Vec<(usize, usize)> {
+    let a = |i: usize, j: usize| cost(i - 1, j - 1).map_or(UNMATCHABLE, i64::from);
+    let mut u = vec![0i64; rows + 1];
+    let mut v = vec![0i64; columns + 1];
+    let mut p = vec![0usize; columns + 1];
+    let mut way = vec![0usize; columns + 1];
+
+    for i in 1..=rows {
+        p[0] = i;
+        let mut j0 = 0;
+        let mut minv = vec![i64::MAX; columns + 1];
+        let mut used = vec![false; columns + 1];
+        loop {
+            used[j0] = true;
+            let i0 = p[j0];
+            let mut delta = i64::MAX;
+            let mut j1 = 0;
+            for j in 1..=columns {
+                if !used[j] {
+                    let cur = a(i0, j) - u[i0] - v[j];
+                    if cur < minv[j] {
+                        minv[j] = cur;
+                        way[j] = j0;
+                    }
+                    if minv[j] < delta {
+                        delta = minv[j];
+                        j1 = j;
+                    }
+                }
+            }
+            for j in 0..=columns {
+                if used[j] {
+                    u[p[j]] += delta;
+                    v[j] -= delta;
+                } else {
+                    minv[j] -= delta;
+                }
+            }
+            j0 = j1;
+            if p[j0] == 0 {
+                break;
+            }
+        }
+        loop {
+            let j1 = way[j0];
+            p[j0] = p[j1];
+            j0 = j1;
+            if j0 == 0 {
+                break;
+            }
+        }
+    }
+
+    (1..=columns)
+        .filter(|&j| p[j] != 0)
+        .map(|j| (p[j] - 1, j - 1))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn total(costs: &[Vec<Option<i32>>], matches: &[(usize, usize)]) -> i32 {
+        matches.iter().map(|&(r, c)| costs[r][c].unwrap()).sum()
+    }
+
+    #[test]
+    fn given_costs_when_greedy_matching_then_pick_cheapest_pairs_first() {
+        let costs = vec![vec![Some(1), Some(2)], vec![Some(2), Some(10)]];
+
+        let matches = greedy_matching(&costs);
+
+        assert_eq!(matches, vec![(0, 0), (1, 1)]);
+        assert_eq!(total(&costs, &matches), 11);
+    }
+
+    #[test]
+    fn given_costs_when_optimal_matching_then_minimise_total_cost() {
+        let costs = vec![vec![Some(1), Some(2)], vec![Some(2), Some(10)]];
+
+        let matches = optimal_matching(&costs);
+
+        assert_eq!(matches, vec![(0, 1), (1, 0)]);
+        assert_eq!(total(&costs, &matches), 4);
+    }
+
+    #[test]
+    fn given_more_rows_than_columns_when_optimal_matching_then_match_every_column() {
+        let costs = vec![vec![Some(5)], vec![Some(1)], vec![Some(3)]];
+
+        let matches = optimal_matching(&costs);
+
+        assert_eq!(matches, vec![(1, 0)]);
+    }
+
+    #[test]
+    fn given_unreachable_pairs_when_matching_then_never_match_them() {
+        let costs = vec![vec![None, Some(4)], vec![None, Some(1)]];
+
+        assert_eq!(greedy_matching(&costs), vec![(1, 1)]);
+        assert_eq!(optimal_matching(&costs), vec![(1, 1)]);
+    }
+
+    #[test]
+    fn given_no_costs_when_matching_then_no_matches() {
+        assert!(greedy_matching(&[]).is_empty());
+        assert!(optimal_matching(&[]).is_empty());
+    }
+}
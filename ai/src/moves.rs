@@ -0,0 +1,265 @@
+use crate::Direction;
+use crate::Order;
+use crate::Position;
+use crate::World;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+#[derive(Debug)]
+pub struct MoveResolver<'a> {
+    world: &'a World,
+    ants: BTreeSet<Position>,
+    holding: BTreeSet<Position>,
+    orders: BTreeMap<Position, Direction>,
+    destinations: BTreeSet<Position>,
+}
+
+impl<'a> MoveResolver<'a> {
+    pub fn new(world: &'a World) -> Self {
+        Self {
+            world,
+            ants: world.own_ants().map(|a| a.pos).collect(),
+            holding: BTreeSet::new(),
+            orders: BTreeMap::new(),
+            destinations: BTreeSet::new(),
+        }
+    }
+
+    pub fn order(&mut self, ant: Position, direction: Direction) -> bool {
+        if !self.is_idle(ant) {
+            return false;
+        }
+        let destination = self.world.geometry().step(ant, direction);
+        if !self.is_free(destination) {
+            return false;
+        }
+        self.orders.insert(ant, direction);
+        self.destinations.insert(destination);
+        true
+    }
+
+    pub fn hold(&mut self, ant: Position) -> bool {
+        if !self.is_idle(ant) || self.destinations.contains(&ant) {
+            return false;
+        }
+        self.holding.insert(ant);
+        true
+    }
+
+    pub fn is_idle(&self, ant: Position) -> bool {
+        self.ants.contains(&ant) && !self.orders.contains_key(&ant) && !self.holding.contains(&ant)
+    }
+
+    pub fn is_free(&self, pos: Position) -> bool {
+        !self.world.is_water(pos)
+            && !self.destinations.contains(&pos)
+            && !self.holding.contains(&pos)
+    }
+
+    pub fn idle_ants(&self) -> impl Iterator<Item = Position> + '_ {
+        self.ants.iter().copied().filter(move |&a| self.is_idle(a))
+    }
+
+    pub fn resolve(self) -> Vec<Order> {
+        self.snapshot()
+    }
+
+    pub fn snapshot(&self) -> Vec<Order> {
+        let geometry = *self.world.geometry();
+        let mut orders = self.orders.clone();
+        loop {
+            let staying: BTreeSet<Position> = self
+                .ants
+                .iter()
+                .copied()
+                .filter(|a| !orders.contains_key(a))
+                .collect();
+            let blocked: Vec<Position> = orders
+                .iter()
+                .filter(|&(&ant, &direction)| {
+                    let destination = geometry.step(ant, direction);
+                    staying.contains(&destination)
+                        || orders
+                            .get(&destination)
+                            .map(|&d| geometry.step(destination, d))
+                            == Some(ant)
+                })
+                .map(|(&ant, _)| ant)
+                .collect();
+            if blocked.is_empty() {
+                break;
+            }
+            for ant in blocked {
+                orders.remove(&ant);
+            }
+        }
+
+        let mut orders: Vec<Order> = orders
+            .into_iter()
+            .map(|(pos, direction)| Order { pos, direction })
+            .collect();
+        orders.sort_by_key(|o| (o.pos.y, o.pos.x));
+        orders
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use crate::test_support::a_config;
+    use crate::test_support::an_empty_turn_info;
+    use crate::test_support::own_ants;
+    use crate::test_support::pos;
+    use crate::Config;
+    use crate::TurnInfo;
+    use std::collections::HashSet;
+
+    fn a_world(water: Vec<Position>, ants: Vec<Position>) -> World {
+        test_support::a_world(
+            &Config {
+                width: 10,
+                height: 10,
+                ..a_config()
+            },
+            &TurnInfo {
+                water,
+                ant: own_ants(ants),
+                ..an_empty_turn_info()
+            },
+        )
+    }
+
+    fn destinations(world: &World, orders: &[Order]) -> Vec<Position> {
+        orders
+            .iter()
+            .map(|o| world.geometry().step(o.pos, o.direction))
+            .collect()
+    }
+
+    #[test]
+    fn given_two_ants_moving_to_same_tile_when_order_then_second_order_is_rejected() {
+        let world = a_world(vec![], vec![pos(1, 1), pos(3, 1)]);
+        let mut moves = MoveResolver::new(&world);
+
+        let first = moves.order(pos(1, 1), Direction::E);
+        let second = moves.order(pos(3, 1), Direction::W);
+
+        assert!(first);
+        assert!(!second);
+        assert_eq!(moves.resolve().len(), 1);
+    }
+
+    #[test]
+    fn given_order_into_water_when_order_then_order_is_rejected() {
+        let world = a_world(vec![pos(1, 0)], vec![pos(1, 1)]);
+        let mut moves = MoveResolver::new(&world);
+
+        let accepted = moves.order(pos(1, 1), Direction::N);
+
+        assert!(!accepted);
+        assert!(moves.resolve().is_empty());
+    }
+
+    #[test]
+    fn given_order_for_position_without_own_ant_when_order_then_order_is_rejected() {
+        let world = a_world(vec![], vec![pos(1, 1)]);
+        let mut moves = MoveResolver::new(&world);
+
+        let accepted = moves.order(pos(5, 5), Direction::N);
+
+        assert!(!accepted);
+    }
+
+    #[test]
+    fn given_order_onto_staying_ant_when_resolve_then_order_is_dropped() {
+        let world = a_world(vec![], vec![pos(1, 1), pos(2, 1)]);
+        let mut moves = MoveResolver::new(&world);
+        moves.order(pos(1, 1), Direction::E);
+
+        let orders = moves.resolve();
+
+        assert!(orders.is_empty());
+    }
+
+    #[test]
+    fn given_chain_of_ants_moving_in_line_when_resolve_then_keep_all_orders() {
+        let world = a_world(vec![], vec![pos(1, 1), pos(2, 1), pos(3, 1)]);
+        let mut moves = MoveResolver::new(&world);
+        moves.order(pos(1, 1), Direction::E);
+        moves.order(pos(2, 1), Direction::E);
+        moves.order(pos(3, 1), Direction::E);
+
+        let orders = moves.resolve();
+
+        assert_eq!(orders.len(), 3);
+    }
+
+    #[test]
+    fn given_chain_ending_at_blocked_ant_when_resolve_then_drop_whole_chain() {
+        let world = a_world(vec![pos(4, 1)], vec![pos(1, 1), pos(2, 1), pos(3, 1)]);
+        let mut moves = MoveResolver::new(&world);
+        moves.order(pos(1, 1), Direction::E);
+        moves.order(pos(2, 1), Direction::E);
+        moves.order(pos(3, 1), Direction::E);
+
+        let orders = moves.resolve();
+
+        assert!(orders.is_empty());
+    }
+
+    #[test]
+    fn given_ants_swapping_places_when_resolve_then_both_stay() {
+        let world = a_world(vec![], vec![pos(1, 1), pos(2, 1)]);
+        let mut moves = MoveResolver::new(&world);
+        moves.order(pos(1, 1), Direction::E);
+        moves.order(pos(2, 1), Direction::W);
+
+        let orders = moves.resolve();
+
+        assert!(orders.is_empty());
+    }
+
+    #[test]
+    fn given_holding_ant_when_order_onto_it_then_order_is_rejected() {
+        let world = a_world(vec![], vec![pos(1, 1), pos(2, 1)]);
+        let mut moves = MoveResolver::new(&world);
+        moves.hold(pos(2, 1));
+
+        let accepted = moves.order(pos(1, 1), Direction::E);
+
+        assert!(!accepted);
+    }
+
+    #[test]
+    fn given_orders_when_snapshot_then_keep_resolving_later_orders() {
+        let world = a_world(vec![], vec![pos(1, 1), pos(5, 5)]);
+        let mut moves = MoveResolver::new(&world);
+        moves.order(pos(1, 1), Direction::E);
+
+        let snapshot = moves.snapshot();
+        moves.order(pos(5, 5), Direction::S);
+
+        assert_eq!(snapshot, vec![Order::new(1, 1, Direction::E)]);
+        assert_eq!(moves.resolve().len(), 2);
+    }
+
+    #[test]
+    fn given_many_random_orders_when_resolve_then_no_two_ants_share_a_tile() {
+        let ants: Vec<Position> = (0..10).flat_map(|x| vec![pos(x, 1), pos(x, 2)]).collect();
+        let world = a_world(vec![pos(3, 0), pos(6, 3)], ants.clone());
+        let mut moves = MoveResolver::new(&world);
+        for (i, &ant) in ants.iter().enumerate() {
+            moves.order(ant, Direction::ALL[i % 4]);
+        }
+
+        let orders = moves.resolve();
+
+        let moved: HashSet<Position> = orders.iter().map(|o| o.pos).collect();
+        let mut occupied: Vec<Position> = destinations(&world, &orders);
+        occupied.extend(ants.iter().filter(|a| !moved.contains(a)));
+        let unique: HashSet<Position> = occupied.iter().copied().collect();
+        assert_eq!(unique.len(), ants.len());
+        assert!(occupied.iter().all(|&p| !world.is_water(p)));
+    }
+}
@@ -0,0 +1,384 @@
+use crate::Direction;
+use crate::Geometry;
+use crate::Position;
+use crate::World;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::iter;
+
+#[derive(Debug)]
+pub struct DistanceField {
+    geometry: Geometry,
+    distances: Vec<Option<i32>>,
+    origins: Vec<Option<Position>>,
+    directions_to_origin: Vec<Option<Direction>>,
+    first_steps: Vec<Option<Direction>>,
+}
+
+impl DistanceField {
+    pub fn distance(&self, pos: Position) -> Option<i32> {
+        self.distances[self.geometry.index(pos)]
+    }
+
+    pub fn origin(&self, pos: Position) -> Option<Position> {
+        self.origins[self.geometry.index(pos)]
+    }
+
+    pub fn direction_to_origin(&self, pos: Position) -> Option<Direction> {
+        self.directions_to_origin[self.geometry.index(pos)]
+    }
+
+    pub fn first_step(&self, pos: Position) -> Option<Direction> {
+        self.first_steps[self.geometry.index(pos)]
+    }
+
+    pub fn reached(&self) -> impl Iterator<Item = (Position, i32)> + '_ {
+        self.distances
+            .iter()
+            .enumerate()
+            .filter_map(move |(i, d)| d.map(|d| (self.geometry.position(i), d)))
+    }
+}
+
+pub fn bfs(world: &World, source: Position) -> DistanceField {
+    multi_source_bfs(world, iter::once(source))
+}
+
+pub fn multi_source_bfs(
+    world: &World,
+    sources: impl IntoIterator<Item = Position>,
+) -> DistanceField {
+    multi_source_bfs_within(world, sources, i32::MAX)
+}
+
+pub fn multi_source_bfs_within(
+    world: &World,
+    sources: impl IntoIterator<Item = Position>,
+    max_distance: i32,
+) -> DistanceField {
+    let geometry = *world.geometry();
+    let size = geometry.size();
+    let mut field = DistanceField {
+        geometry,
+        distances: vec![None; size],
+        origins: vec![None; size],
+        directions_to_origin: vec![None; size],
+        first_steps: vec![None; size],
+    };
+
+    let mut queue = VecDeque::new();
+    for source in sources {
+        let source = geometry.wrap(source);
+        let i = geometry.index(source);
+        if field.distances[i].is_none() {
+            field.distances[i] = Some(0);
+            field.origins[i] = Some(source);
+            queue.push_back(source);
+        }
+    }
+
+    while let Some(pos) = queue.pop_front() {
+        let i = geometry.index(pos);
+        let distance = field.distances[i].unwrap_or(0);
+        if distance >= max_distance {
+            continue;
+        }
+        for &direction in &Direction::ALL {
+            let next = geometry.step(pos, direction);
+            let j = geometry.index(next);
+            if field.distances[j].is_some() || world.is_water(next) {
+                continue;
+            }
+            field.distances[j] = Some(distance + 1);
+            field.origins[j] = field.origins[i];
+            field.directions_to_origin[j] = Some(direction.opposite());
+            field.first_steps[j] = field.first_steps[i].or(Some(direction));
+            queue.push_back(next);
+        }
+    }
+    field
+}
+
+pub fn astar(world: &World, from: Position, to: Position) -> Option<Vec<Position>> {
+    let geometry = world.geometry();
+    let from = geometry.wrap(from);
+    let to = geometry.wrap(to);
+    if world.is_water(to) {
+        return None;
+    }
+    if from == to {
+        return Some(vec![]);
+    }
+
+    let mut costs: HashMap<Position, i32> = HashMap::new();
+    let mut parents: HashMap<Position, Position> = HashMap::new();
+    let mut open = BinaryHeap::new();
+    costs.insert(from, 0);
+    open.push(Reverse((
+        geometry.manhattan_distance(from, to),
+        0,
+        geometry.index(from),
+    )));
+
+    while let Some(Reverse((_, cost, i))) = open.pop() {
+        let pos = geometry.position(i);
+        if pos == to {
+            let mut path = vec![to];
+            let mut pos = to;
+            while let Some(&parent) = parents.get(&pos) {
+                if parent == from {
+                    break;
+                }
+                path.push(parent);
+                pos = parent;
+            }
+            path.reverse();
+            return Some(path);
+        }
+        if costs.get(&pos).is_some_and(|&c| c < cost) {
+            continue;
+        }
+        for next in geometry.neighbours(pos).iter().copied() {
+            if world.is_water(next) {
+                continue;
+            }
+            let next_cost = cost + 1;
+            if costs.get(&next).is_none_or(|&c| next_cost < c) {
+                costs.insert(next, next_cost);
+                parents.insert(next, pos);
+                let estimate = next_cost + geometry.manhattan_distance(next, to);
+                open.push(Reverse((estimate, next_cost, geometry.index(next))));
+            }
+        }
+    }
+    None
+}
+
+#[derive(Debug)]
+struct CachedPath {
+    target: Position,
+    steps: VecDeque<Position>,
+}
+
+#[derive(Debug, Default)]
+pub struct PathCache {
+    paths: HashMap<Position, CachedPath>,
+}
+
+impl PathCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn next_step(
+        &mut self,
+        world: &World,
+        ant: Position,
+        target: Position,
+    ) -> Option<Direction> {
+        let geometry = world.geometry();
+        let ant = geometry.wrap(ant);
+        let target = geometry.wrap(target);
+
+        let mut path = match self.paths.remove(&ant) {
+            Some(path) if path.target == target && !is_blocked(world, &path) => path,
+            _ => CachedPath {
+                target,
+                steps: astar(world, ant, target)?.into(),
+            },
+        };
+        let next = path.steps.pop_front()?;
+        if !path.steps.is_empty() {
+            self.paths.insert(next, path);
+        }
+        geometry.directions(ant, next).first().copied()
+    }
+
+    pub fn target(&self, ant: Position) -> Option<Position> {
+        self.paths.get(&ant).map(|p| p.target)
+    }
+
+    pub fn path(&self, ant: Position) -> Option<impl Iterator<Item = &Position>> {
+        self.paths.get(&ant).map(|p| p.steps.iter())
+    }
+
+    pub fn invalidate_blocked(&mut self, world: &World) {
+        self.paths.retain(|_, path| !is_blocked(world, path));
+    }
+
+    pub fn retain_ants(&mut self, ants: &[Position]) {
+        self.paths.retain(|pos, _| ants.contains(pos));
+    }
+
+    pub fn len(&self) -> usize {
+        self.paths.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+}
+
+fn is_blocked(world: &World, path: &CachedPath) -> bool {
+    path.steps.iter().any(|&pos| world.is_water(pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support;
+    use crate::test_support::a_config;
+    use crate::test_support::an_empty_turn_info;
+    use crate::test_support::pos;
+    use crate::Config;
+    use crate::TurnInfo;
+
+    fn a_world(water: Vec<Position>) -> World {
+        test_support::a_world(
+            &Config {
+                width: 10,
+                height: 10,
+                ..a_config()
+            },
+            &TurnInfo {
+                water,
+                ..an_empty_turn_info()
+            },
+        )
+    }
+
+    #[test]
+    fn given_open_map_when_bfs_then_distance_is_wrapped_manhattan_distance() {
+        let world = a_world(vec![]);
+
+        let field = bfs(&world, pos(1, 1));
+
+        assert_eq!(field.distance(pos(1, 1)), Some(0));
+        assert_eq!(field.distance(pos(4, 2)), Some(4));
+        assert_eq!(field.distance(pos(9, 9)), Some(4));
+    }
+
+    #[test]
+    fn given_water_wall_when_bfs_then_path_goes_around_water() {
+        let world = a_world(vec![pos(2, 0), pos(2, 1), pos(2, 2), pos(2, 9), pos(2, 8)]);
+
+        let field = bfs(&world, pos(1, 1));
+
+        assert_eq!(field.distance(pos(2, 1)), None);
+        assert_eq!(field.distance(pos(3, 1)), Some(6));
+    }
+
+    #[test]
+    fn given_unreachable_tile_when_bfs_then_no_distance() {
+        let world = a_world(vec![pos(5, 4), pos(4, 5), pos(6, 5), pos(5, 6)]);
+
+        let field = bfs(&world, pos(1, 1));
+
+        assert_eq!(field.distance(pos(5, 5)), None);
+        assert_eq!(field.direction_to_origin(pos(5, 5)), None);
+    }
+
+    #[test]
+    fn given_multiple_sources_when_multi_source_bfs_then_distance_to_nearest_source() {
+        let world = a_world(vec![]);
+
+        let field = multi_source_bfs(&world, vec![pos(1, 1), pos(6, 1)]);
+
+        assert_eq!(field.distance(pos(5, 1)), Some(1));
+        assert_eq!(field.origin(pos(5, 1)), Some(pos(6, 1)));
+        assert_eq!(field.direction_to_origin(pos(5, 1)), Some(Direction::E));
+        assert_eq!(field.origin(pos(2, 2)), Some(pos(1, 1)));
+    }
+
+    #[test]
+    fn given_source_when_bfs_then_first_step_leads_from_source() {
+        let world = a_world(vec![]);
+
+        let field = bfs(&world, pos(1, 1));
+
+        assert_eq!(field.first_step(pos(1, 1)), None);
+        assert_eq!(field.first_step(pos(1, 4)), Some(Direction::S));
+        assert_eq!(field.first_step(pos(8, 1)), Some(Direction::W));
+    }
+
+    #[test]
+    fn given_open_map_when_astar_then_return_shortest_path_ending_at_target() {
+        let world = a_world(vec![]);
+
+        let path = astar(&world, pos(1, 1), pos(8, 2)).unwrap();
+
+        assert_eq!(path.len(), 4);
+        assert_eq!(path.last(), Some(&pos(8, 2)));
+        assert_eq!(world.geometry().manhattan_distance(pos(1, 1), path[0]), 1);
+    }
+
+    #[test]
+    fn given_water_wall_when_astar_then_path_avoids_water() {
+        let world = a_world(vec![pos(2, 0), pos(2, 1), pos(2, 2), pos(2, 9), pos(2, 8)]);
+
+        let path = astar(&world, pos(1, 1), pos(3, 1)).unwrap();
+
+        assert_eq!(path.len(), 6);
+        assert!(path.iter().all(|&p| !world.is_water(p)));
+    }
+
+    #[test]
+    fn given_unreachable_target_when_astar_then_none() {
+        let world = a_world(vec![pos(5, 4), pos(4, 5), pos(6, 5), pos(5, 6)]);
+
+        let path = astar(&world, pos(1, 1), pos(5, 5));
+
+        assert_eq!(path, None);
+    }
+
+    #[test]
+    fn given_cached_path_when_next_step_from_next_position_then_follow_cached_path() {
+        let world = a_world(vec![]);
+        let mut cache = PathCache::new();
+
+        let first = cache.next_step(&world, pos(1, 1), pos(1, 4));
+        let second = cache.next_step(&world, pos(1, 2), pos(1, 4));
+
+        assert_eq!(first, Some(Direction::S));
+        assert_eq!(second, Some(Direction::S));
+        assert_eq!(cache.path(pos(1, 3)).map(|p| p.count()), Some(1));
+        assert_eq!(cache.target(pos(1, 3)), Some(pos(1, 4)));
+    }
+
+    #[test]
+    fn given_water_discovered_on_cached_path_when_invalidate_blocked_then_path_is_dropped() {
+        let world = a_world(vec![]);
+        let mut cache = PathCache::new();
+        cache.next_step(&world, pos(1, 1), pos(1, 5));
+        let world = a_world(vec![pos(1, 4)]);
+
+        cache.invalidate_blocked(&world);
+
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn given_water_discovered_on_cached_path_when_next_step_then_reroute() {
+        let world = a_world(vec![]);
+        let mut cache = PathCache::new();
+        cache.next_step(&world, pos(1, 1), pos(1, 5));
+        let world = a_world(vec![pos(1, 3)]);
+
+        let step = cache.next_step(&world, pos(1, 2), pos(1, 5));
+
+        assert_matches!(step, Some(Direction::E) | Some(Direction::W));
+    }
+
+    #[test]
+    fn given_max_distance_when_multi_source_bfs_within_then_stop_at_max_distance() {
+        let world = a_world(vec![]);
+
+        let field = multi_source_bfs_within(&world, vec![pos(1, 1)], 2);
+
+        assert_eq!(field.distance(pos(3, 1)), Some(2));
+        assert_eq!(field.distance(pos(4, 1)), None);
+        assert_eq!(field.reached().count(), 13);
+    }
+}
@@ -0,0 +1,68 @@
+use crate::Config;
+use crate::OrderDraft;
+use crate::PlayerEntity;
+use crate::Position;
+use crate::TurnContext;
+use crate::TurnInfo;
+use crate::World;
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+pub fn pos(x: i32, y: i32) -> Position {
+    Position { x, y }
+}
+
+pub fn entity(id: i32, x: i32, y: i32) -> PlayerEntity {
+    PlayerEntity { id, pos: pos(x, y) }
+}
+
+pub fn own_ants(positions: Vec<Position>) -> Vec<PlayerEntity> {
+    positions
+        .into_iter()
+        .map(|pos| PlayerEntity { id: 0, pos })
+        .collect()
+}
+
+pub fn a_config() -> Config {
+    Config {
+        load_time: 3000,
+        turn_time: 1000,
+        width: 20,
+        height: 20,
+        turns: 500,
+        view_radius2: 55,
+        attack_radius2: 5,
+        food_gathering_radius2: 1,
+        player_seed: 42,
+        players: None,
+        cutoff_turn: None,
+        cutoff_percent: None,
+        extra: HashMap::new(),
+    }
+}
+
+pub fn an_empty_turn_info() -> TurnInfo {
+    TurnInfo {
+        water: vec![],
+        food: vec![],
+        ant_hill: vec![],
+        ant: vec![],
+        dead_ant: vec![],
+    }
+}
+
+pub fn a_world(config: &Config, turn_info: &TurnInfo) -> World {
+    let mut world = World::new(config);
+    world.update(1, turn_info);
+    world
+}
+
+pub fn a_context() -> TurnContext {
+    TurnContext {
+        turn: 1,
+        turns: 500,
+        deadline: Some(Instant::now() + Duration::from_secs(1)),
+        draft: OrderDraft::new(),
+    }
+}
@@ -0,0 +1,109 @@
+use crate::Geometry;
+use crate::Position;
+
+#[derive(Debug)]
+pub struct Visibility {
+    geometry: Geometry,
+    offsets: Vec<(i32, i32)>,
+    visible: Vec<bool>,
+}
+
+impl Visibility {
+    pub fn new(geometry: Geometry, radius2: i32) -> Self {
+        Self {
+            geometry,
+            offsets: circle_offsets(radius2),
+            visible: vec![false; geometry.size()],
+        }
+    }
+
+    pub fn update(&mut self, viewers: impl IntoIterator<Item = Position>) {
+        self.visible.iter_mut().for_each(|v| *v = false);
+        for viewer in viewers {
+            for &(dx, dy) in &self.offsets {
+                let pos = Position {
+                    x: viewer.x + dx,
+                    y: viewer.y + dy,
+                };
+                self.visible[self.geometry.index(pos)] = true;
+            }
+        }
+    }
+
+    pub fn is_visible(&self, pos: Position) -> bool {
+        self.visible[self.geometry.index(pos)]
+    }
+
+    pub fn visible_positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.visible
+            .iter()
+            .enumerate()
+            .filter(|(_, &v)| v)
+            .map(move |(i, _)| self.geometry.position(i))
+    }
+}
+
+pub fn circle_offsets(radius2: i32) -> Vec<(i32, i32)> {
+    let radius = (radius2 as f64).sqrt() as i32;
+    let mut offsets = vec![];
+    for dy in -radius..=radius {
+        for dx in -radius..=radius {
+            if dx * dx + dy * dy <= radius2 {
+                offsets.push((dx, dy));
+            }
+        }
+    }
+    offsets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::pos;
+
+    #[test]
+    fn when_circle_offsets_then_include_offsets_within_radius_only() {
+        let offsets = circle_offsets(5);
+
+        assert_eq!(offsets.len(), 21);
+        assert!(offsets.contains(&(0, 0)));
+        assert!(offsets.contains(&(2, 1)));
+        assert!(offsets.contains(&(-1, -2)));
+        assert!(!offsets.contains(&(2, 2)));
+        assert!(!offsets.contains(&(0, 3)));
+    }
+
+    #[test]
+    fn given_viewer_when_update_then_tiles_within_radius_are_visible() {
+        let mut visibility = Visibility::new(Geometry::new(20, 30), 4);
+
+        visibility.update(vec![pos(10, 10)]);
+
+        assert!(visibility.is_visible(pos(10, 10)));
+        assert!(visibility.is_visible(pos(10, 8)));
+        assert!(visibility.is_visible(pos(11, 11)));
+        assert!(!visibility.is_visible(pos(12, 11)));
+        assert_eq!(visibility.visible_positions().count(), 13);
+    }
+
+    #[test]
+    fn given_viewer_at_edge_when_update_then_visibility_wraps_around() {
+        let mut visibility = Visibility::new(Geometry::new(20, 30), 4);
+
+        visibility.update(vec![pos(0, 0)]);
+
+        assert!(visibility.is_visible(pos(19, 0)));
+        assert!(visibility.is_visible(pos(0, 28)));
+        assert!(visibility.is_visible(pos(19, 29)));
+    }
+
+    #[test]
+    fn given_viewer_moved_away_when_update_then_old_tiles_are_not_visible() {
+        let mut visibility = Visibility::new(Geometry::new(20, 30), 4);
+        visibility.update(vec![pos(0, 0)]);
+
+        visibility.update(vec![pos(10, 10)]);
+
+        assert!(!visibility.is_visible(pos(0, 0)));
+    }
+}
@@ -0,0 +1,288 @@
+use crate::Config;
+use crate::Geometry;
+use crate::PlayerEntity;
+use crate::Position;
+use crate::TurnInfo;
+use crate::Visibility;
+
+#[derive(Debug)]
+pub struct World {
+    geometry: Geometry,
+    visibility: Visibility,
+    turn: i32,
+    water: Vec<bool>,
+    last_seen: Vec<Option<i32>>,
+    food: Vec<Position>,
+    hills: Vec<PlayerEntity>,
+    ants: Vec<PlayerEntity>,
+}
+
+impl World {
+    pub fn new(config: &Config) -> Self {
+        let geometry = Geometry::from_config(config);
+        let size = geometry.size();
+        Self {
+            geometry,
+            visibility: Visibility::new(geometry, config.view_radius2),
+            turn: 0,
+            water: vec![false; size],
+            last_seen: vec![None; size],
+            food: vec![],
+            hills: vec![],
+            ants: vec![],
+        }
+    }
+
+    pub fn update(&mut self, turn: i32, turn_info: &TurnInfo) {
+        self.turn = turn;
+        self.visibility
+            .update(turn_info.ant.iter().filter(|a| a.is_own()).map(|a| a.pos));
+
+        for &pos in &turn_info.water {
+            let i = self.geometry.index(pos);
+            self.water[i] = true;
+        }
+
+        let ants = &turn_info.ant;
+        let visibility = &self.visibility;
+        self.hills.retain(|h| {
+            turn_info.ant_hill.contains(h)
+                || !(visibility.is_visible(h.pos)
+                    || ants.iter().any(|a| a.pos == h.pos && a.id != h.id))
+        });
+        for hill in &turn_info.ant_hill {
+            if !self.hills.contains(hill) {
+                self.hills.push(*hill);
+            }
+        }
+
+        self.food.retain(|f| {
+            !turn_info.food.contains(f)
+                && !visibility.is_visible(*f)
+                && !ants.iter().any(|a| a.pos == *f)
+        });
+        self.food.extend(turn_info.food.iter().copied());
+
+        self.ants = turn_info.ant.clone();
+
+        let turn = self.turn;
+        for pos in self.visibility.visible_positions() {
+            let i = self.geometry.index(pos);
+            self.last_seen[i] = Some(turn);
+        }
+        let seen = turn_info
+            .water
+            .iter()
+            .chain(turn_info.food.iter())
+            .chain(turn_info.ant_hill.iter().map(|h| &h.pos))
+            .chain(turn_info.ant.iter().map(|a| &a.pos))
+            .chain(turn_info.dead_ant.iter().map(|a| &a.pos));
+        for &pos in seen {
+            let i = self.geometry.index(pos);
+            self.last_seen[i] = Some(turn);
+        }
+    }
+
+    pub fn geometry(&self) -> &Geometry {
+        &self.geometry
+    }
+
+    pub fn turn(&self) -> i32 {
+        self.turn
+    }
+
+    pub fn is_water(&self, pos: Position) -> bool {
+        self.water[self.geometry.index(pos)]
+    }
+
+    pub fn is_visible(&self, pos: Position) -> bool {
+        self.visibility.is_visible(pos)
+    }
+
+    pub fn last_seen(&self, pos: Position) -> Option<i32> {
+        self.last_seen[self.geometry.index(pos)]
+    }
+
+    pub fn food(&self) -> &[Position] {
+        &self.food
+    }
+
+    pub fn hills(&self) -> &[PlayerEntity] {
+        &self.hills
+    }
+
+    pub fn own_hills(&self) -> impl Iterator<Item = &PlayerEntity> {
+        self.hills.iter().filter(|h| h.is_own())
+    }
+
+    pub fn enemy_hills(&self) -> impl Iterator<Item = &PlayerEntity> {
+        self.hills.iter().filter(|h| !h.is_own())
+    }
+
+    pub fn ants(&self) -> &[PlayerEntity] {
+        &self.ants
+    }
+
+    pub fn own_ants(&self) -> impl Iterator<Item = &PlayerEntity> {
+        self.ants.iter().filter(|a| a.is_own())
+    }
+
+    pub fn enemy_ants(&self) -> impl Iterator<Item = &PlayerEntity> {
+        self.ants.iter().filter(|a| !a.is_own())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::a_config;
+    use crate::test_support::an_empty_turn_info;
+    use crate::test_support::entity;
+
+    fn a_tall_config() -> Config {
+        Config {
+            height: 30,
+            ..a_config()
+        }
+    }
+
+    #[test]
+    fn given_water_in_first_turn_when_update_then_water_is_remembered() {
+        let mut world = World::new(&a_tall_config());
+        let mut turn_info = an_empty_turn_info();
+        turn_info.water = vec![Position { x: 3, y: 4 }];
+
+        world.update(1, &turn_info);
+        world.update(2, &an_empty_turn_info());
+
+        assert!(world.is_water(Position { x: 3, y: 4 }));
+        assert!(!world.is_water(Position { x: 4, y: 3 }));
+    }
+
+    #[test]
+    fn given_skipped_turns_when_update_then_use_game_turn() {
+        let mut world = World::new(&a_tall_config());
+        let mut turn_info = an_empty_turn_info();
+        turn_info.ant = vec![entity(0, 3, 4)];
+
+        world.update(7, &turn_info);
+
+        assert_eq!(world.turn(), 7);
+        assert_eq!(world.last_seen(Position { x: 3, y: 4 }), Some(7));
+    }
+
+    #[test]
+    fn given_food_not_reported_again_when_update_then_food_is_remembered() {
+        let mut world = World::new(&a_tall_config());
+        let mut turn_info = an_empty_turn_info();
+        turn_info.food = vec![Position { x: 3, y: 4 }];
+
+        world.update(1, &turn_info);
+        world.update(2, &turn_info);
+        world.update(3, &an_empty_turn_info());
+
+        assert_eq!(world.food(), &[Position { x: 3, y: 4 }]);
+    }
+
+    #[test]
+    fn given_ant_on_remembered_food_when_update_then_food_is_forgotten() {
+        let mut world = World::new(&a_tall_config());
+        let mut turn_info = an_empty_turn_info();
+        turn_info.food = vec![Position { x: 3, y: 4 }];
+        world.update(1, &turn_info);
+        let mut turn_info = an_empty_turn_info();
+        turn_info.ant = vec![entity(0, 3, 4)];
+
+        world.update(2, &turn_info);
+
+        assert!(world.food().is_empty());
+    }
+
+    #[test]
+    fn given_enemy_hill_not_reported_again_when_update_then_hill_is_remembered() {
+        let mut world = World::new(&a_tall_config());
+        let mut turn_info = an_empty_turn_info();
+        turn_info.ant_hill = vec![entity(1, 3, 4), entity(0, 10, 10)];
+
+        world.update(1, &turn_info);
+        world.update(2, &an_empty_turn_info());
+
+        let enemy_hills: Vec<_> = world.enemy_hills().collect();
+        let own_hills: Vec<_> = world.own_hills().collect();
+        assert_eq!(enemy_hills, vec![&entity(1, 3, 4)]);
+        assert_eq!(own_hills, vec![&entity(0, 10, 10)]);
+    }
+
+    #[test]
+    fn given_own_ant_on_enemy_hill_when_hill_not_reported_then_hill_is_razed() {
+        let mut world = World::new(&a_tall_config());
+        let mut turn_info = an_empty_turn_info();
+        turn_info.ant_hill = vec![entity(1, 3, 4)];
+        world.update(1, &turn_info);
+        let mut turn_info = an_empty_turn_info();
+        turn_info.ant = vec![entity(0, 3, 4)];
+
+        world.update(2, &turn_info);
+
+        assert_eq!(world.enemy_hills().count(), 0);
+    }
+
+    #[test]
+    fn given_visible_food_not_reported_when_update_then_food_is_forgotten() {
+        let mut world = World::new(&a_tall_config());
+        let mut turn_info = an_empty_turn_info();
+        turn_info.food = vec![Position { x: 3, y: 4 }];
+        world.update(1, &turn_info);
+        let mut turn_info = an_empty_turn_info();
+        turn_info.ant = vec![entity(0, 5, 5)];
+
+        world.update(2, &turn_info);
+
+        assert!(world.food().is_empty());
+    }
+
+    #[test]
+    fn given_visible_enemy_hill_not_reported_when_update_then_hill_is_razed() {
+        let mut world = World::new(&a_tall_config());
+        let mut turn_info = an_empty_turn_info();
+        turn_info.ant_hill = vec![entity(1, 3, 4)];
+        world.update(1, &turn_info);
+        let mut turn_info = an_empty_turn_info();
+        turn_info.ant = vec![entity(0, 5, 5)];
+
+        world.update(2, &turn_info);
+
+        assert_eq!(world.enemy_hills().count(), 0);
+    }
+
+    #[test]
+    fn given_own_ant_when_update_then_tiles_in_view_radius_are_seen() {
+        let mut world = World::new(&a_tall_config());
+        let mut turn_info = an_empty_turn_info();
+        turn_info.ant = vec![entity(0, 3, 4), entity(1, 12, 20)];
+
+        world.update(1, &turn_info);
+
+        assert!(world.is_visible(Position { x: 8, y: 9 }));
+        assert_eq!(world.last_seen(Position { x: 8, y: 9 }), Some(1));
+        assert!(!world.is_visible(Position { x: 12, y: 15 }));
+        assert_eq!(world.last_seen(Position { x: 12, y: 15 }), None);
+    }
+
+    #[test]
+    fn given_reported_tiles_when_update_then_last_seen_is_current_turn() {
+        let mut world = World::new(&a_tall_config());
+        let mut turn_info = an_empty_turn_info();
+        turn_info.ant = vec![entity(0, 3, 4)];
+        world.update(1, &turn_info);
+        let mut turn_info = an_empty_turn_info();
+        turn_info.food = vec![Position { x: 5, y: 6 }];
+
+        world.update(2, &turn_info);
+
+        assert_eq!(world.turn(), 2);
+        assert_eq!(world.last_seen(Position { x: 3, y: 4 }), Some(1));
+        assert_eq!(world.last_seen(Position { x: 5, y: 6 }), Some(2));
+        assert_eq!(world.last_seen(Position { x: 12, y: 20 }), None);
+    }
+}
@@ -1,12 +1,44 @@
+use std::env;
 use std::io;
 use std::io::prelude::*;
+use std::io::BufWriter;
+use std::process::ExitCode;
+use std::time::Duration;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let record = match args.as_slice() {
+        [] => None,
+        [flag, path] if flag == "--record" => Some(path),
+        _ => {
+            eprintln!("usage: ants-bot [--record <path>]");
+            return ExitCode::from(2);
+        }
+    };
 
-fn main() {
     let stdin = io::stdin();
     let stdin_iter = stdin.lock().lines().map(|l| l.unwrap());
-    let mut client = client::Client::new(stdin_iter, |o| println!("{}", o));
+    let stdout = io::stdout();
+    let mut client = client::Client::new(stdin_iter, BufWriter::new(stdout.lock()));
+    if let Some(path) = record {
+        match client::Recorder::create(path) {
+            Ok(recorder) => client.record(recorder),
+            Err(error) => eprintln!("cannot record replay to '{}': {}", path, error),
+        }
+    }
 
-    let _config = client.set_up();
-    let mut agent = ai::Agent {};
-    client.run(&mut agent).unwrap();
+    let agent = ai::Agent::default();
+    let result = client
+        .set_up()
+        .and_then(|_| client.run_with_safety_margin(agent, Duration::from_millis(50)));
+    if let Some(error) = client.recorder_error() {
+        eprintln!("cannot record replay: {}", error);
+    }
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("{}", error);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -1,51 +1,200 @@
 mod parser;
+mod replay;
 mod unparser;
+mod worker;
 
 use ai::Config;
+use ai::EndInfo;
+use ai::OrderDraft;
+use ai::TurnContext;
+use ai::TurnInfo;
 use ai::TurnTaker;
 use parser::Parser;
 use parser::Turn;
+use std::io;
+use std::time::Duration;
+use std::time::Instant;
 use unparser::Unparser;
+use worker::Worker;
 
 pub use parser::Error;
+pub use parser::ErrorKind;
+pub use parser::ParseMode;
+pub use replay::Entry;
+pub use replay::Event;
+pub use replay::Recorder;
+pub use replay::Replay;
+pub use replay::ReplayError;
+pub use unparser::Output;
 
 pub struct Client<I, O> {
     parser: Parser<I>,
     unparser: Unparser<O>,
+    config: Option<Config>,
+    recorder: Option<Recorder>,
+    recorder_error: Option<io::Error>,
 }
 
 impl<T, I, O> Client<I, O>
 where
     T: AsRef<str>,
     I: Iterator<Item = T>,
-    O: Fn(&str),
+    O: Output,
 {
     pub fn new(input: impl IntoIterator<Item = T, IntoIter = I>, output: O) -> Self {
         Self {
             parser: Parser::new(input),
             unparser: Unparser::new(output),
+            config: None,
+            recorder: None,
+            recorder_error: None,
         }
     }
 
+    pub fn with_mode(
+        input: impl IntoIterator<Item = T, IntoIter = I>,
+        output: O,
+        mode: ParseMode,
+    ) -> Self {
+        Self {
+            parser: Parser::with_mode(input, mode),
+            unparser: Unparser::new(output),
+            config: None,
+            recorder: None,
+            recorder_error: None,
+        }
+    }
+
+    pub fn record(&mut self, recorder: Recorder) {
+        self.parser.record(recorder.clone());
+        self.unparser.record(recorder.clone());
+        self.recorder = Some(recorder);
+    }
+
+    pub fn recorder_error(&self) -> Option<&io::Error> {
+        self.recorder_error.as_ref()
+    }
+
     pub fn set_up(&mut self) -> Result<Config, Error> {
-        self.parser.next_start_turn()
+        let config = match self.parser.next_start_turn() {
+            Ok(config) => config,
+            Err(error) => {
+                self.finish_recording();
+                return Err(error);
+            }
+        };
+        self.config = Some(config.clone());
+        Ok(config)
     }
 
     pub fn run(&mut self, turn_taker: &mut impl TurnTaker) -> Result<(), Error> {
-        self.unparser.output_go();
+        let started = Instant::now();
+        if let Some(config) = &self.config {
+            turn_taker.setup(config);
+        }
+        let end_info = self.run_turns(started, |turn_info, context, unparser| {
+            unparser.output_orders(turn_taker.take_turn(turn_info, context))
+        })?;
+        if let Some(end_info) = end_info {
+            turn_taker.end(end_info);
+        }
+        Ok(())
+    }
+
+    pub fn run_with_safety_margin(
+        &mut self,
+        mut turn_taker: impl TurnTaker + Send + 'static,
+        margin: Duration,
+    ) -> Result<(), Error> {
+        let started = Instant::now();
+        if let Some(config) = &self.config {
+            turn_taker.setup(config);
+        }
+        let mut worker = Worker::spawn(turn_taker);
+        let end_info = self.run_turns(started, |turn_info, context, unparser| {
+            let cutoff = context
+                .deadline
+                .map(|deadline| deadline.checked_sub(margin).unwrap_or(deadline));
+            unparser.output_orders(worker.take_turn(turn_info, context, cutoff))
+        })?;
+        if let Some(end_info) = end_info {
+            worker.end(end_info);
+        }
+        Ok(())
+    }
+
+    fn run_turns(
+        &mut self,
+        started: Instant,
+        take_turn: impl FnMut(TurnInfo, &TurnContext, &mut Unparser<O>) -> io::Result<()>,
+    ) -> Result<Option<EndInfo>, Error> {
+        let result = self.play_turns(started, take_turn);
+        self.finish_recording();
+        result
+    }
+
+    fn play_turns(
+        &mut self,
+        started: Instant,
+        mut take_turn: impl FnMut(TurnInfo, &TurnContext, &mut Unparser<O>) -> io::Result<()>,
+    ) -> Result<Option<EndInfo>, Error> {
+        self.unparser
+            .output_go()
+            .map_err(|e| self.output_error(e))?;
+        self.record_turn_time(started);
+        let mut end_info = None;
         while let Some(turn) = self.parser.next_turn() {
             match turn {
                 Turn::Normal(turn) => {
-                    let orders = turn_taker.take_turn(turn?);
-                    self.unparser.output_orders(orders);
+                    let turn_info = turn?;
+                    let context = self.turn_context();
+                    let started = Instant::now();
+                    take_turn(turn_info, &context, &mut self.unparser)
+                        .map_err(|e| self.output_error(e))?;
+                    self.record_turn_time(started);
                 }
                 Turn::End(turn) => {
-                    turn_taker.end(turn?);
+                    end_info = Some(turn?);
                     break;
                 }
             }
         }
-        Ok(())
+        Ok(end_info)
+    }
+
+    fn finish_recording(&mut self) {
+        if let Some(Err(error)) = self.recorder.as_ref().map(Recorder::finish) {
+            self.recorder_error = Some(error);
+        }
+    }
+
+    fn output_error(&self, error: io::Error) -> Error {
+        Error {
+            kind: ErrorKind::CannotWriteOutput,
+            turn: self.parser.turn(),
+            line: self.parser.line(),
+            text: Some(error.to_string()),
+            parameter: None,
+        }
+    }
+
+    fn record_turn_time(&self, started: Instant) {
+        if let Some(recorder) = &self.recorder {
+            recorder.turn_time(self.parser.turn(), started.elapsed());
+        }
+    }
+
+    fn turn_context(&self) -> TurnContext {
+        let turn_started = self.parser.turn_started();
+        TurnContext {
+            turn: self.parser.turn(),
+            turns: self.config.as_ref().map_or(0, |c| c.turns),
+            deadline: self
+                .config
+                .as_ref()
+                .map(|c| turn_started + Duration::from_millis(c.turn_time.max(0) as u64)),
+            draft: OrderDraft::new(),
+        }
     }
 }
 
@@ -59,9 +208,13 @@ mod tests {
     use ai::Direction;
     use ai::EndInfo;
     use ai::Order;
-    use ai::TurnInfo;
+    use std::cell::Cell;
     use std::cell::RefCell;
     use std::iter;
+    use std::sync::mpsc;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::thread;
 
     fn a_start_turn_input() -> impl Iterator<Item = &'static str> {
         vec![
@@ -81,7 +234,7 @@ mod tests {
     }
 
     fn a_normal_turn_input(turn: &str) -> impl Iterator<Item = &str> {
-        iter::once(turn).chain(vec!["f 6 5", "w 7 6", "a 10 9 0", "h 7 12 0", "go"].into_iter())
+        iter::once(turn).chain(vec!["f 6 5", "w 7 6", "a 10 9 0", "h 7 12 0", "go"])
     }
 
     fn a_end_turn_input() -> impl Iterator<Item = &'static str> {
@@ -98,10 +251,34 @@ mod tests {
     }
 
     #[derive(Debug)]
+    #[allow(dead_code)]
     enum Callback {
-        TakeTurn(TurnInfo),
+        Setup(Config),
+        TakeTurn(TurnInfo, TurnContext),
         End(EndInfo),
         Output(String),
+        Flush(Instant),
+    }
+
+    struct TestOutput<'a> {
+        callbacks: &'a RefCell<Vec<Callback>>,
+        buffer: Vec<u8>,
+    }
+
+    impl<'a> io::Write for TestOutput<'a> {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.buffer.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            let mut callbacks = self.callbacks.borrow_mut();
+            for line in String::from_utf8_lossy(&self.buffer).lines() {
+                callbacks.push(Callback::Output(line.to_string()));
+            }
+            callbacks.push(Callback::Flush(Instant::now()));
+            self.buffer.clear();
+            Ok(())
+        }
     }
 
     struct TestTurnTaker<'a> {
@@ -110,10 +287,16 @@ mod tests {
     }
 
     impl<'a> TurnTaker for TestTurnTaker<'a> {
-        fn take_turn(&mut self, turn_info: TurnInfo) -> Vec<Order> {
+        fn setup(&mut self, config: &Config) {
             self.callbacks
                 .borrow_mut()
-                .push(Callback::TakeTurn(turn_info));
+                .push(Callback::Setup(config.clone()));
+        }
+
+        fn take_turn(&mut self, turn_info: TurnInfo, context: &TurnContext) -> Vec<Order> {
+            self.callbacks
+                .borrow_mut()
+                .push(Callback::TakeTurn(turn_info, context.clone()));
             self.orders.clone()
         }
 
@@ -122,10 +305,55 @@ mod tests {
         }
     }
 
+    struct SlowTurnTaker {
+        draft: Vec<Order>,
+        orders: Vec<Order>,
+        delay: Duration,
+    }
+
+    impl TurnTaker for SlowTurnTaker {
+        fn take_turn(&mut self, _turn_info: TurnInfo, context: &TurnContext) -> Vec<Order> {
+            context.draft.publish(self.draft.clone());
+            thread::sleep(self.delay);
+            self.orders.clone()
+        }
+
+        fn end(&mut self, _end_info: EndInfo) {}
+    }
+
+    struct GatedTurnTaker {
+        draft: Vec<Order>,
+        gate: mpsc::Receiver<()>,
+        contexts: Arc<Mutex<Vec<TurnContext>>>,
+    }
+
+    impl TurnTaker for GatedTurnTaker {
+        fn take_turn(&mut self, _turn_info: TurnInfo, context: &TurnContext) -> Vec<Order> {
+            self.contexts.lock().unwrap().push(context.clone());
+            if context.turn == 1 {
+                context.draft.publish(self.draft.clone());
+                let _ = self.gate.recv_timeout(Duration::from_secs(5));
+            }
+            vec![Order::new(context.turn, 0, Direction::N)]
+        }
+
+        fn end(&mut self, _end_info: EndInfo) {}
+    }
+
+    fn a_short_turn_time_input() -> impl Iterator<Item = &'static str> {
+        a_start_turn_input().map(|l| {
+            if l.starts_with("turntime") {
+                "turntime 200"
+            } else {
+                l
+            }
+        })
+    }
+
     #[test]
     fn given_input_when_set_up_then_according_to_input() {
         let input = a_start_turn_input();
-        let mut client = Client::new(input, |_| {});
+        let mut client = Client::new(input, io::sink());
 
         let config = client.set_up();
 
@@ -140,7 +368,11 @@ mod tests {
                 view_radius2: 55,
                 attack_radius2: 5,
                 food_gathering_radius2: 1,
-                player_seed: 42
+                player_seed: 42,
+                players: None,
+                cutoff_turn: None,
+                cutoff_percent: None,
+                ..
             })
         );
     }
@@ -148,7 +380,7 @@ mod tests {
     #[test]
     fn given_invalid_set_up_input_when_set_up_then_return_error() {
         let input = a_start_turn_input().take(2);
-        let mut client = Client::new(input, |_| {});
+        let mut client = Client::new(input, io::sink());
 
         let result = client.set_up();
 
@@ -158,7 +390,10 @@ mod tests {
     #[test]
     fn given_input_when_run_then_according_to_input() {
         let callbacks = RefCell::new(vec![]);
-        let save_output = |o: &str| callbacks.borrow_mut().push(Callback::Output(o.to_string()));
+        let output = TestOutput {
+            callbacks: &callbacks,
+            buffer: vec![],
+        };
 
         let mut turn_taker = TestTurnTaker {
             callbacks: &callbacks,
@@ -171,22 +406,374 @@ mod tests {
             .chain(a_normal_turn_input("turn 1"))
             .chain(a_normal_turn_input("turn 2"))
             .chain(a_end_turn_input());
-        let mut client = Client::new(input, save_output);
+        let mut client = Client::new(input, output);
         let _ = client.set_up();
 
         client.run(&mut turn_taker).unwrap();
 
         let calls = callbacks.borrow();
         let mut calls = calls.iter();
+        assert_matches!(
+            calls.next(),
+            Some(Callback::Setup(Config { width: 20, .. }))
+        );
         assert_matches!(calls.next(), Some(Callback::Output(s)) if s == "go");
-        assert_matches!(calls.next(), Some(Callback::TakeTurn(_)));
+        assert_matches!(calls.next(), Some(Callback::Flush(_)));
+        assert_matches!(
+            calls.next(),
+            Some(Callback::TakeTurn(
+                _,
+                TurnContext {
+                    turn: 1,
+                    turns: 500,
+                    ..
+                }
+            ))
+        );
         assert_matches!(calls.next(), Some(Callback::Output(s)) if s == "o 12 34 N");
         assert_matches!(calls.next(), Some(Callback::Output(s)) if s == "o 56 78 W");
         assert_matches!(calls.next(), Some(Callback::Output(s)) if s == "go");
-        assert_matches!(calls.next(), Some(Callback::TakeTurn(_)));
+        assert_matches!(calls.next(), Some(Callback::Flush(_)));
+        assert_matches!(
+            calls.next(),
+            Some(Callback::TakeTurn(
+                _,
+                TurnContext {
+                    turn: 2,
+                    turns: 500,
+                    ..
+                }
+            ))
+        );
         assert_matches!(calls.next(), Some(Callback::Output(s)) if s == "o 12 34 N");
         assert_matches!(calls.next(), Some(Callback::Output(s)) if s == "o 56 78 W");
         assert_matches!(calls.next(), Some(Callback::Output(s)) if s == "go");
+        assert_matches!(calls.next(), Some(Callback::Flush(_)));
+        assert_matches!(calls.next(), Some(Callback::End(_)));
+    }
+
+    #[test]
+    fn given_turn_time_when_run_then_deadline_is_turn_time_after_turn_line() {
+        let callbacks = RefCell::new(vec![]);
+        let mut turn_taker = TestTurnTaker {
+            callbacks: &callbacks,
+            orders: vec![],
+        };
+        let input = a_start_turn_input().chain(a_normal_turn_input("turn 1"));
+        let mut client = Client::new(input, io::sink());
+        let _ = client.set_up();
+        let before = Instant::now();
+
+        client.run(&mut turn_taker).unwrap();
+
+        let after = Instant::now();
+        let calls = callbacks.borrow();
+        let context = calls.iter().find_map(|c| match c {
+            Callback::TakeTurn(_, context) => Some(context.clone()),
+            _ => None,
+        });
+        let deadline = context.unwrap().deadline.unwrap();
+        assert!(deadline >= before + Duration::from_millis(1000));
+        assert!(deadline <= after + Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn given_no_set_up_when_run_then_turn_has_no_deadline() {
+        let callbacks = RefCell::new(vec![]);
+        let mut turn_taker = TestTurnTaker {
+            callbacks: &callbacks,
+            orders: vec![],
+        };
+        let mut client = Client::new(a_normal_turn_input("turn 1"), io::sink());
+
+        client.run(&mut turn_taker).unwrap();
+
+        let calls = callbacks.borrow();
+        assert!(calls.iter().any(|c| matches!(
+            c,
+            Callback::TakeTurn(
+                _,
+                TurnContext {
+                    turn: 1,
+                    deadline: None,
+                    ..
+                }
+            )
+        )));
+    }
+
+    #[test]
+    fn given_fast_turn_taker_when_run_with_safety_margin_then_output_returned_orders() {
+        let mut output = vec![];
+        let turn_taker = SlowTurnTaker {
+            draft: vec![Order::new(1, 2, Direction::N)],
+            orders: vec![Order::new(3, 4, Direction::S)],
+            delay: Duration::from_millis(0),
+        };
+        let input = a_short_turn_time_input()
+            .chain(a_normal_turn_input("turn 1"))
+            .chain(a_end_turn_input());
+        let mut client = Client::new(input, &mut output);
+        let _ = client.set_up();
+
+        client
+            .run_with_safety_margin(turn_taker, Duration::from_millis(100))
+            .unwrap();
+
+        assert_eq!(output, b"go\no 4 3 S\ngo\n");
+    }
+
+    #[test]
+    fn given_slow_turn_taker_when_run_with_safety_margin_then_output_draft_without_waiting() {
+        let (gate, gate_receiver) = mpsc::channel();
+        let turn_taker = GatedTurnTaker {
+            draft: vec![Order::new(1, 2, Direction::N)],
+            gate: gate_receiver,
+            contexts: Arc::new(Mutex::new(vec![])),
+        };
+        let input = a_short_turn_time_input()
+            .chain(a_normal_turn_input("turn 1"))
+            .chain(a_end_turn_input());
+        let mut output = vec![];
+        let mut client = Client::new(input, &mut output);
+        let _ = client.set_up();
+
+        client
+            .run_with_safety_margin(turn_taker, Duration::from_millis(100))
+            .unwrap();
+        drop(gate);
+
+        assert_eq!(output, b"go\no 2 1 N\ngo\n");
+    }
+
+    #[test]
+    fn given_turn_taker_overrunning_turn_one_when_run_with_safety_margin_then_turn_two_gets_full_budget(
+    ) {
+        let (gate, gate_receiver) = mpsc::channel();
+        let contexts = Arc::new(Mutex::new(vec![]));
+        let turn_taker = GatedTurnTaker {
+            draft: vec![Order::new(1, 2, Direction::N)],
+            gate: gate_receiver,
+            contexts: contexts.clone(),
+        };
+        let turn_two_read = Cell::new(None);
+        let input = a_start_turn_input()
+            .chain(a_normal_turn_input("turn 1"))
+            .chain(a_normal_turn_input("turn 2"))
+            .chain(a_end_turn_input())
+            .inspect(|&l| {
+                if l == "turn 2" {
+                    turn_two_read.set(Some(Instant::now()));
+                    let _ = gate.send(());
+                }
+            });
+        let mut output = vec![];
+        let mut client = Client::new(input, &mut output);
+        let _ = client.set_up();
+
+        client
+            .run_with_safety_margin(turn_taker, Duration::from_millis(500))
+            .unwrap();
+
+        assert_eq!(output, b"go\no 2 1 N\ngo\no 0 2 N\ngo\n");
+        let contexts = contexts.lock().unwrap();
+        assert_eq!(contexts[1].turn, 2);
+        let turn_two_read = turn_two_read.get().unwrap();
+        assert!(contexts[1].deadline.unwrap() >= turn_two_read + Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn given_recorder_when_run_then_replay_reruns_same_game() {
+        let path = std::env::temp_dir().join(format!("ants-client-{}.replay", std::process::id()));
+        let mut turn_taker = SlowTurnTaker {
+            draft: vec![],
+            orders: vec![Order::new(3, 4, Direction::S)],
+            delay: Duration::from_millis(0),
+        };
+        let input: Vec<_> = a_start_turn_input()
+            .chain(a_normal_turn_input("turn 1"))
+            .chain(a_end_turn_input())
+            .collect();
+        let mut output = vec![];
+        let mut client = Client::new(input.clone(), &mut output);
+        client.record(Recorder::create(&path).unwrap());
+        client.set_up().unwrap();
+
+        client.run(&mut turn_taker).unwrap();
+        let replay = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let replay = replay.unwrap();
+        assert_eq!(replay.input().collect::<Vec<_>>(), input);
+        assert_eq!(
+            replay.output().collect::<Vec<_>>(),
+            vec!["go", "o 4 3 S", "go"]
+        );
+        assert_eq!(
+            replay
+                .turn_times()
+                .map(|(turn, _)| turn)
+                .collect::<Vec<_>>(),
+            vec![0, 1]
+        );
+        let mut rerun = vec![];
+        let mut client = Client::new(replay.input(), &mut rerun);
+        client.set_up().unwrap();
+        client.run(&mut turn_taker).unwrap();
+        assert_eq!(rerun, output);
+    }
+
+    #[test]
+    fn given_recorded_agent_game_when_replay_then_agent_reissues_same_orders() {
+        let path = std::env::temp_dir().join(format!("ants-agent-{}.replay", std::process::id()));
+        let turn = |turn| {
+            iter::once(turn).chain(vec![
+                "w 4 4",
+                "w 4 5",
+                "f 6 9",
+                "f 12 3",
+                "h 8 8 0",
+                "h 15 15 1",
+                "a 8 8 0",
+                "a 8 9 0",
+                "a 9 8 0",
+                "a 7 8 0",
+                "a 8 7 0",
+                "a 10 10 0",
+                "a 6 6 0",
+                "a 14 14 1",
+                "go",
+            ])
+        };
+        let input: Vec<_> = a_start_turn_input()
+            .chain(turn("turn 1"))
+            .chain(turn("turn 2"))
+            .chain(turn("turn 3"))
+            .chain(a_end_turn_input())
+            .collect();
+        let mut output = vec![];
+        let mut client = Client::new(input, &mut output);
+        client.record(Recorder::create(&path).unwrap());
+        client.set_up().unwrap();
+
+        client.run(&mut ai::Agent::default()).unwrap();
+        let replay = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        let replay = replay.unwrap();
+        let mut rerun = vec![];
+        let mut client = Client::new(replay.input(), &mut rerun);
+        client.set_up().unwrap();
+        client.run(&mut ai::Agent::default()).unwrap();
+        assert!(replay.output().filter(|l| l.starts_with("o ")).count() > 3);
+        assert_eq!(
+            String::from_utf8(rerun)
+                .unwrap()
+                .lines()
+                .collect::<Vec<_>>(),
+            replay.output().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn given_broken_recorder_when_run_then_finish_game() {
+        let mut output = vec![];
+        let mut turn_taker = SlowTurnTaker {
+            draft: vec![],
+            orders: vec![Order::new(3, 4, Direction::S)],
+            delay: Duration::from_millis(0),
+        };
+        let input = a_start_turn_input()
+            .chain(a_normal_turn_input("turn 1"))
+            .chain(a_end_turn_input());
+        let mut client = Client::new(input, &mut output);
+        client.record(Recorder::new(io::Cursor::new([0u8; 0])));
+        client.set_up().unwrap();
+
+        let result = client.run(&mut turn_taker);
+        let recorder_error = client.recorder_error().map(io::Error::kind);
+
+        assert_matches!(result, Ok(()));
+        assert_eq!(output, b"go\no 4 3 S\ngo\n");
+        assert_eq!(recorder_error, Some(io::ErrorKind::WriteZero));
+    }
+
+    #[test]
+    fn given_recorder_when_run_fails_then_replay_is_written() {
+        let path = std::env::temp_dir().join(format!("ants-failed-{}.replay", std::process::id()));
+        let callbacks = RefCell::new(vec![]);
+        let mut turn_taker = TestTurnTaker {
+            callbacks: &callbacks,
+            orders: vec![],
+        };
+        let input = a_start_turn_input().chain(vec!["turn 1", "x 1 2", "go"]);
+        let mut output = vec![];
+        let mut client = Client::with_mode(input, &mut output, ParseMode::Strict);
+        client.record(Recorder::create(&path).unwrap());
+        client.set_up().unwrap();
+
+        let result = client.run(&mut turn_taker);
+        let replay = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_matches!(result, Err(Error { turn: 1, .. }));
+        assert!(client.recorder_error().is_none());
+        let replay = replay.unwrap();
+        assert_eq!(replay.input().last(), Some("x 1 2"));
+        assert_eq!(replay.output().collect::<Vec<_>>(), vec!["go"]);
+    }
+
+    #[test]
+    fn given_recorder_when_set_up_fails_then_replay_is_written() {
+        let path = std::env::temp_dir().join(format!("ants-setup-{}.replay", std::process::id()));
+        let input = vec!["turn 0", "loadtime INVALID", "ready"];
+        let mut client = Client::with_mode(input.clone(), io::sink(), ParseMode::Strict);
+        client.record(Recorder::create(&path).unwrap());
+
+        let result = client.set_up();
+        let replay = Replay::load(&path);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_matches!(result, Err(_));
+        assert_eq!(replay.unwrap().input().collect::<Vec<_>>(), input);
+    }
+
+    #[test]
+    fn given_broken_output_when_run_then_return_output_error() {
+        let callbacks = RefCell::new(vec![]);
+        let mut turn_taker = TestTurnTaker {
+            callbacks: &callbacks,
+            orders: vec![],
+        };
+        let input = a_start_turn_input().chain(a_normal_turn_input("turn 1"));
+        let mut client = Client::new(input, &mut [0u8; 0][..]);
+        let _ = client.set_up();
+
+        let result = client.run(&mut turn_taker);
+
+        assert_matches!(
+            result,
+            Err(Error {
+                kind: ErrorKind::CannotWriteOutput,
+                turn: 0,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn given_no_set_up_when_run_then_do_not_call_setup() {
+        let callbacks = RefCell::new(vec![]);
+        let mut turn_taker = TestTurnTaker {
+            callbacks: &callbacks,
+            orders: vec![],
+        };
+        let mut client = Client::new(a_end_turn_input(), io::sink());
+
+        client.run(&mut turn_taker).unwrap();
+
+        let calls = callbacks.borrow();
+        let mut calls = calls.iter();
         assert_matches!(calls.next(), Some(Callback::End(_)));
     }
 
@@ -198,7 +785,7 @@ mod tests {
             orders: vec![],
         };
         let input = a_start_turn_input().chain(a_normal_turn_input("turn 1").take(2));
-        let mut client = Client::new(input, |_| {});
+        let mut client = Client::new(input, io::sink());
         let _ = client.set_up();
 
         let result = client.run(&mut turn_taker);
@@ -214,7 +801,7 @@ mod tests {
             orders: vec![],
         };
         let input = a_start_turn_input().chain(a_end_turn_input().take(2));
-        let mut client = Client::new(input, |_| {});
+        let mut client = Client::new(input, io::sink());
         let _ = client.set_up();
 
         let result = client.run(&mut turn_taker);
@@ -232,16 +819,16 @@ mod tests {
         let input = a_start_turn_input()
             .chain(a_end_turn_input())
             .chain(a_normal_turn_input("turn 1"));
-        let mut client = Client::new(input, |_| {});
+        let mut client = Client::new(input, io::sink());
         let _ = client.set_up();
 
         client.run(&mut turn_taker).unwrap();
 
-        let take_turn_called = callbacks.borrow().iter().any(|c| match c {
-            Callback::TakeTurn(_) => true,
-            _ => false,
-        });
-        assert_eq!(take_turn_called, false);
+        let take_turn_called = callbacks
+            .borrow()
+            .iter()
+            .any(|c| matches!(c, Callback::TakeTurn(..)));
+        assert!(!take_turn_called);
     }
 
     #[test]
@@ -255,13 +842,42 @@ mod tests {
             .chain(a_start_turn_input())
             .chain(iter::once("INVALID INPUT"))
             .chain(a_end_turn_input());
-        let mut client = Client::new(input, |_| {});
+        let mut client = Client::new(input, io::sink());
         let _ = client.set_up();
 
         client.run(&mut turn_taker).unwrap();
 
         let calls = callbacks.borrow();
         let mut calls = calls.iter();
+        assert_matches!(calls.next(), Some(Callback::Setup(_)));
         assert_matches!(calls.next(), Some(Callback::End(_)));
     }
+
+    #[test]
+    fn given_invalid_input_when_run_in_strict_mode_then_return_error() {
+        let callbacks = RefCell::new(vec![]);
+        let mut turn_taker = TestTurnTaker {
+            callbacks: &callbacks,
+            orders: vec![],
+        };
+        let input = a_start_turn_input()
+            .chain(iter::once("INVALID INPUT"))
+            .chain(a_end_turn_input());
+        let mut client = Client::with_mode(input, io::sink(), ParseMode::Strict);
+        client.set_up().unwrap();
+
+        let result = client.run(&mut turn_taker);
+
+        assert_matches!(
+            result,
+            Err(Error {
+                kind: ErrorKind::CannotParseTurnInfo,
+                ..
+            })
+        );
+        assert!(callbacks
+            .borrow()
+            .iter()
+            .all(|c| !matches!(c, Callback::End(_))));
+    }
 }
@@ -1,14 +1,62 @@
+use crate::replay::Recorder;
 use ai::Config;
 use ai::EndInfo;
 use ai::PlayerEntity;
 use ai::Position;
 use ai::TurnInfo;
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::time::Instant;
+
+struct ConfigParameter {
+    line: usize,
+    text: String,
+    value: String,
+}
 
-#[derive(Debug, PartialEq)]
-pub enum Error {
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ErrorKind {
     CannotParseGameConfig,
     CannotParseTurnInfo,
     CannotParseEndInfo,
+    CannotWriteOutput,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    pub kind: ErrorKind,
+    pub turn: i32,
+    pub line: usize,
+    pub text: Option<String>,
+    pub parameter: Option<String>,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let what = match self.kind {
+            ErrorKind::CannotParseGameConfig => "cannot parse game config",
+            ErrorKind::CannotParseTurnInfo => "cannot parse turn info",
+            ErrorKind::CannotParseEndInfo => "cannot parse end info",
+            ErrorKind::CannotWriteOutput => "cannot write output",
+        };
+        write!(f, "{} in turn {} at line {}", what, self.turn, self.line)?;
+        if let Some(parameter) = &self.parameter {
+            write!(f, ", parameter '{}'", parameter)?;
+        }
+        if let Some(text) = &self.text {
+            write!(f, ": '{}'", text)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ParseMode {
+    Lenient,
+    Strict,
 }
 
 #[derive(Debug)]
@@ -19,6 +67,12 @@ pub enum Turn {
 
 pub struct Parser<I> {
     input: I,
+    mode: ParseMode,
+    bounds: Option<(i32, i32)>,
+    line: usize,
+    turn: i32,
+    turn_started: Instant,
+    recorder: Option<Recorder>,
 }
 
 impl<T, I> Parser<I>
@@ -27,73 +81,235 @@ where
     I: Iterator<Item = T>,
 {
     pub fn new(input: impl IntoIterator<Item = T, IntoIter = I>) -> Self {
+        Self::with_mode(input, ParseMode::Lenient)
+    }
+
+    pub fn with_mode(input: impl IntoIterator<Item = T, IntoIter = I>, mode: ParseMode) -> Self {
         Self {
             input: input.into_iter(),
+            mode,
+            bounds: None,
+            line: 0,
+            turn: 0,
+            turn_started: Instant::now(),
+            recorder: None,
         }
     }
 
+    pub fn record(&mut self, recorder: Recorder) {
+        self.recorder = Some(recorder);
+    }
+
+    pub fn turn(&self) -> i32 {
+        self.turn
+    }
+
+    pub fn turn_started(&self) -> Instant {
+        self.turn_started
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
     pub fn next_start_turn(&mut self) -> Result<Config, Error> {
-        self.input
-            .by_ref()
-            .skip_while(|l| l.as_ref() != "turn 0")
-            .next();
-        self.extract_game_config()
+        while let Some(line) = self.next_line() {
+            if line.as_ref() == "turn 0" {
+                break;
+            }
+            self.unknown(ErrorKind::CannotParseGameConfig, line.as_ref())?;
+        }
+        self.turn = 0;
+        self.turn_started = Instant::now();
+        let config = self.extract_game_config()?;
+        self.bounds = Some((config.width, config.height));
+        Ok(config)
     }
 
     pub fn next_turn(&mut self) -> Option<Turn> {
-        while let Some(line) = self.input.next() {
-            if line.as_ref().starts_with("turn ") {
-                return Some(Turn::Normal(self.extract_turn_info()));
+        while let Some(line) = self.next_line() {
+            if let Some(turn) = line.as_ref().strip_prefix("turn ") {
+                self.turn_started = Instant::now();
+                match turn.trim().parse() {
+                    Ok(turn) => {
+                        self.turn = turn;
+                        return Some(Turn::Normal(self.extract_turn_info()));
+                    }
+                    Err(_) if self.mode == ParseMode::Lenient => continue,
+                    Err(_) => {
+                        return Some(Turn::Normal(Err(self.error(
+                            ErrorKind::CannotParseTurnInfo,
+                            Some(line.as_ref()),
+                            "turn",
+                        ))))
+                    }
+                }
             } else if line.as_ref() == "end" {
                 return Some(Turn::End(self.extract_end_info()));
+            } else if let Err(error) = self.unknown(ErrorKind::CannotParseTurnInfo, line.as_ref()) {
+                return Some(Turn::Normal(Err(error)));
             }
         }
         None
     }
 
+    fn next_line(&mut self) -> Option<T> {
+        let line = self.input.next();
+        if let Some(line) = &line {
+            self.line += 1;
+            if let Some(recorder) = &self.recorder {
+                recorder.input(line.as_ref());
+            }
+        }
+        line
+    }
+
+    fn unknown(&self, kind: ErrorKind, line: &str) -> Result<(), Error> {
+        match (self.mode, line.split_whitespace().next()) {
+            (ParseMode::Strict, Some(token)) => Err(self.error(kind, Some(line), token)),
+            _ => Ok(()),
+        }
+    }
+
+    fn position(
+        &self,
+        kind: ErrorKind,
+        line: &str,
+        row: &str,
+        col: &str,
+    ) -> Result<Option<Position>, Error> {
+        if self.mode == ParseMode::Lenient {
+            return Ok(parse_position(row, col));
+        }
+        let (width, height) = self.bounds.unwrap_or((i32::MAX, i32::MAX));
+        let row =
+            parse_coordinate(row, height).ok_or_else(|| self.error(kind, Some(line), "row"))?;
+        let col =
+            parse_coordinate(col, width).ok_or_else(|| self.error(kind, Some(line), "col"))?;
+        Ok(Some(Position { y: row, x: col }))
+    }
+
+    fn player(
+        &self,
+        kind: ErrorKind,
+        line: &str,
+        row: &str,
+        col: &str,
+        id: &str,
+    ) -> Result<Option<PlayerEntity>, Error> {
+        if self.mode == ParseMode::Lenient {
+            return Ok(parse_player(row, col, id));
+        }
+        let pos = self.position(kind, line, row, col)?;
+        match id.parse() {
+            Ok(id) if id >= 0 => Ok(pos.map(|pos| PlayerEntity { id, pos })),
+            _ => Err(self.error(kind, Some(line), "id")),
+        }
+    }
+
+    fn error(&self, kind: ErrorKind, text: Option<&str>, parameter: &str) -> Error {
+        Error {
+            kind,
+            turn: self.turn,
+            line: self.line,
+            text: text.map(String::from),
+            parameter: Some(parameter.to_string()),
+        }
+    }
+
     fn extract_game_config(&mut self) -> Result<Config, Error> {
-        let mut load_time: Option<i32> = None;
-        let mut turn_time: Option<i32> = None;
-        let mut width: Option<i32> = None;
-        let mut height: Option<i32> = None;
-        let mut turns: Option<i32> = None;
-        let mut view_radius2: Option<i32> = None;
-        let mut attack_radius2: Option<i32> = None;
-        let mut food_gathering_radius2: Option<i32> = None;
-        let mut player_seed: Option<i64> = None;
-
-        for line in self.input.by_ref() {
-            let mut type_value = line.as_ref().split_whitespace();
-            let parameter_type = type_value.next();
-            let parameter_value = type_value.next();
-            match (parameter_type, parameter_value) {
-                (Some("loadtime"), Some(v)) => load_time = v.parse().ok(),
-                (Some("turntime"), Some(v)) => turn_time = v.parse().ok(),
-                (Some("rows"), Some(v)) => height = v.parse().ok(),
-                (Some("cols"), Some(v)) => width = v.parse().ok(),
-                (Some("turns"), Some(v)) => turns = v.parse().ok(),
-                (Some("viewradius2"), Some(v)) => view_radius2 = v.parse().ok(),
-                (Some("attackradius2"), Some(v)) => attack_radius2 = v.parse().ok(),
-                (Some("spawnradius2"), Some(v)) => food_gathering_radius2 = v.parse().ok(),
-                (Some("player_seed"), Some(v)) => player_seed = v.parse().ok(),
+        let mut parameters: HashMap<String, ConfigParameter> = HashMap::new();
+
+        while let Some(line) = self.next_line() {
+            let line = line.as_ref();
+            let mut type_value = line.trim().splitn(2, char::is_whitespace);
+            match (type_value.next(), type_value.next().map(str::trim)) {
                 (Some("ready"), _) => break,
-                _ => (),
+                (Some(parameter_type), Some(value)) if !value.is_empty() => {
+                    let parameter = ConfigParameter {
+                        line: self.line,
+                        text: line.to_string(),
+                        value: value.to_string(),
+                    };
+                    let duplicate = parameters
+                        .insert(parameter_type.to_string(), parameter)
+                        .is_some();
+                    if duplicate && self.mode == ParseMode::Strict {
+                        return Err(self.error(
+                            ErrorKind::CannotParseGameConfig,
+                            Some(line),
+                            parameter_type,
+                        ));
+                    }
+                }
+                _ => self.unknown(ErrorKind::CannotParseGameConfig, line)?,
             }
         }
 
         Ok(Config {
-            load_time: load_time.ok_or(Error::CannotParseGameConfig)?,
-            turn_time: turn_time.ok_or(Error::CannotParseGameConfig)?,
-            width: width.ok_or(Error::CannotParseGameConfig)?,
-            height: height.ok_or(Error::CannotParseGameConfig)?,
-            turns: turns.ok_or(Error::CannotParseGameConfig)?,
-            view_radius2: view_radius2.ok_or(Error::CannotParseGameConfig)?,
-            attack_radius2: attack_radius2.ok_or(Error::CannotParseGameConfig)?,
-            food_gathering_radius2: food_gathering_radius2.ok_or(Error::CannotParseGameConfig)?,
-            player_seed: player_seed.ok_or(Error::CannotParseGameConfig)?,
+            load_time: self.required_parameter(&mut parameters, "loadtime")?,
+            turn_time: self.required_parameter(&mut parameters, "turntime")?,
+            width: self.required_parameter(&mut parameters, "cols")?,
+            height: self.required_parameter(&mut parameters, "rows")?,
+            turns: self.required_parameter(&mut parameters, "turns")?,
+            view_radius2: self.required_parameter(&mut parameters, "viewradius2")?,
+            attack_radius2: self.required_parameter(&mut parameters, "attackradius2")?,
+            food_gathering_radius2: self.required_parameter(&mut parameters, "spawnradius2")?,
+            player_seed: self.required_parameter(&mut parameters, "player_seed")?,
+            players: self.optional_parameter(&mut parameters, "players")?,
+            cutoff_turn: self.optional_parameter(&mut parameters, "cutoff_turn")?,
+            cutoff_percent: self.optional_parameter(&mut parameters, "cutoff_percent")?,
+            extra: parameters
+                .into_iter()
+                .map(|(name, parameter)| (name, parameter.value))
+                .collect(),
         })
     }
 
+    fn required_parameter<V: FromStr>(
+        &self,
+        parameters: &mut HashMap<String, ConfigParameter>,
+        name: &str,
+    ) -> Result<V, Error> {
+        self.parse_parameter(parameters, name)?
+            .ok_or_else(|| self.error(ErrorKind::CannotParseGameConfig, None, name))
+    }
+
+    fn optional_parameter<V: FromStr>(
+        &self,
+        parameters: &mut HashMap<String, ConfigParameter>,
+        name: &str,
+    ) -> Result<Option<V>, Error> {
+        match self.parse_parameter(parameters, name) {
+            Err(_) if self.mode == ParseMode::Lenient => Ok(None),
+            result => result,
+        }
+    }
+
+    fn parse_parameter<V: FromStr>(
+        &self,
+        parameters: &mut HashMap<String, ConfigParameter>,
+        name: &str,
+    ) -> Result<Option<V>, Error> {
+        match parameters.remove(name) {
+            Some(parameter) => match self.mode {
+                ParseMode::Lenient => parameter.value.split_whitespace().next().unwrap_or(""),
+                ParseMode::Strict => parameter.value.as_str(),
+            }
+            .parse()
+            .map(Some)
+            .map_err(|_| Error {
+                line: parameter.line,
+                ..self.error(
+                    ErrorKind::CannotParseGameConfig,
+                    Some(&parameter.text),
+                    name,
+                )
+            }),
+            None => Ok(None),
+        }
+    }
+
     fn extract_turn_info(&mut self) -> Result<TurnInfo, Error> {
         let mut water: Vec<Position> = vec![];
         let mut food: Vec<Position> = vec![];
@@ -101,23 +317,30 @@ where
         let mut ant_hill: Vec<PlayerEntity> = vec![];
         let mut dead_ant: Vec<PlayerEntity> = vec![];
 
-        for line in self.input.by_ref() {
-            let mut l = line.as_ref().split_whitespace();
+        let kind = ErrorKind::CannotParseTurnInfo;
+        while let Some(line) = self.next_line() {
+            let line = line.as_ref();
+            let mut l = line.split_whitespace();
             let parameter = (l.next(), l.next(), l.next(), l.next());
             match parameter {
-                (Some("w"), Some(row), Some(col), _) => parse_position(row, col)
+                (Some("w"), Some(row), Some(col), _) => self
+                    .position(kind, line, row, col)?
                     .into_iter()
                     .for_each(|pos| water.push(pos)),
-                (Some("f"), Some(row), Some(col), _) => parse_position(row, col)
+                (Some("f"), Some(row), Some(col), _) => self
+                    .position(kind, line, row, col)?
                     .into_iter()
                     .for_each(|pos| food.push(pos)),
-                (Some("a"), Some(row), Some(col), Some(id)) => parse_player(row, col, id)
+                (Some("a"), Some(row), Some(col), Some(id)) => self
+                    .player(kind, line, row, col, id)?
                     .into_iter()
                     .for_each(|player| ant.push(player)),
-                (Some("h"), Some(row), Some(col), Some(id)) => parse_player(row, col, id)
+                (Some("h"), Some(row), Some(col), Some(id)) => self
+                    .player(kind, line, row, col, id)?
                     .into_iter()
                     .for_each(|player| ant_hill.push(player)),
-                (Some("d"), Some(row), Some(col), Some(id)) => parse_player(row, col, id)
+                (Some("d"), Some(row), Some(col), Some(id)) => self
+                    .player(kind, line, row, col, id)?
                     .into_iter()
                     .for_each(|player| dead_ant.push(player)),
                 (Some("go"), _, _, _) => {
@@ -129,43 +352,58 @@ where
                         dead_ant,
                     })
                 }
-                _ => (),
+                _ => self.unknown(kind, line)?,
             }
         }
-        Err(Error::CannotParseTurnInfo)
+        Err(self.error(kind, None, "go"))
     }
 
     fn extract_end_info(&mut self) -> Result<EndInfo, Error> {
-        let mut no_of_players: Option<usize> = None;
-        let mut scores: Option<Vec<i32>> = None;
-
-        for line in self.input.by_ref() {
-            let mut l = line.as_ref().split_whitespace();
+        let mut no_of_players: Result<usize, Error> =
+            Err(self.error(ErrorKind::CannotParseEndInfo, None, "players"));
+        let mut scores: Result<Vec<i32>, Error> =
+            Err(self.error(ErrorKind::CannotParseEndInfo, None, "score"));
+
+        while let Some(line) = self.next_line() {
+            let line = line.as_ref();
+            let mut l = line.split_whitespace();
             let parameter = (l.next(), l.next());
             match parameter {
-                (Some("players"), Some(players)) => no_of_players = players.parse().ok(),
+                (Some("players"), Some(players)) => {
+                    no_of_players = players.parse().map_err(|_| {
+                        self.error(ErrorKind::CannotParseEndInfo, Some(line), "players")
+                    })
+                }
                 (Some("score"), first_score) => {
-                    scores = Some(
-                        first_score
-                            .into_iter()
-                            .chain(l)
-                            .filter_map(|p| p.parse().ok())
-                            .collect(),
-                    );
+                    scores = first_score
+                        .into_iter()
+                        .chain(l)
+                        .map(|p| p.parse().ok())
+                        .collect::<Option<Vec<i32>>>()
+                        .ok_or_else(|| {
+                            self.error(ErrorKind::CannotParseEndInfo, Some(line), "score")
+                        });
                     break;
                 }
-                _ => (),
+                _ => self.unknown(ErrorKind::CannotParseEndInfo, line)?,
             }
         }
 
-        let turn_info = self
-            .extract_turn_info()
-            .or(Err(Error::CannotParseEndInfo))?;
-        match (no_of_players, scores) {
-            (Some(no_of_players), Some(scores)) if no_of_players == scores.len() => {
-                Ok(EndInfo { scores, turn_info })
-            }
-            _ => Err(Error::CannotParseEndInfo),
+        let score_line = self.line;
+        let turn_info = self.extract_turn_info().map_err(|e| Error {
+            kind: ErrorKind::CannotParseEndInfo,
+            ..e
+        });
+        let no_of_players = no_of_players?;
+        let scores = scores?;
+        let turn_info = turn_info?;
+        if no_of_players == scores.len() {
+            Ok(EndInfo { scores, turn_info })
+        } else {
+            Err(Error {
+                line: score_line,
+                ..self.error(ErrorKind::CannotParseEndInfo, None, "score")
+            })
         }
     }
 }
@@ -178,6 +416,10 @@ fn parse_player(row: &str, col: &str, id: &str) -> Option<PlayerEntity> {
     }
 }
 
+fn parse_coordinate(value: &str, size: i32) -> Option<i32> {
+    value.parse().ok().filter(|&v| v >= 0 && v < size)
+}
+
 fn parse_position(row: &str, col: &str) -> Option<Position> {
     let row_col = (row.parse().ok(), col.parse().ok());
     match row_col {
@@ -273,7 +515,7 @@ mod tests {
 
         let result = parser.next_start_turn().unwrap_err();
 
-        assert_eq!(result, Error::CannotParseGameConfig);
+        assert_eq!(result.kind, ErrorKind::CannotParseGameConfig);
     }
 
     macro_rules! missing_parameter_tests {
@@ -287,7 +529,9 @@ mod tests {
 
 		let result = parser.next_start_turn().unwrap_err();
 
-		assert_eq!(Error::CannotParseGameConfig, result);
+		assert_eq!(ErrorKind::CannotParseGameConfig, result.kind);
+		assert_eq!(Some($value.to_string()), result.parameter);
+		assert_eq!(None, result.text);
         }
     )*
     }
@@ -323,7 +567,9 @@ mod tests {
 
 		let result = parser.next_start_turn().unwrap_err();
 
-		assert_eq!(Error::CannotParseGameConfig, result);
+		assert_eq!(ErrorKind::CannotParseGameConfig, result.kind);
+		assert_eq!(Some($value.to_string()), result.parameter);
+		assert_eq!(Some(format!("{} INVALID_VALUE", $value)), result.text);
         }
     )*
     }
@@ -340,6 +586,133 @@ mod tests {
         given_input_with_invalid_parameter_value_player_seed_when_next_start_turn_then_return_error: "player_seed",
     }
 
+    #[test]
+    fn given_invalid_parameter_value_when_next_start_turn_then_error_has_line_and_turn() {
+        let input = vec!["turn 0", "loadtime 3000", "turntime INVALID_VALUE", "ready"];
+        let mut parser = Parser::new(input);
+
+        let result = parser.next_start_turn().unwrap_err();
+
+        assert_eq!(result.turn, 0);
+        assert_eq!(result.line, 3);
+        assert_eq!(
+            result.to_string(),
+            "cannot parse game config in turn 0 at line 3, parameter 'turntime': 'turntime INVALID_VALUE'"
+        );
+    }
+
+    #[test]
+    fn given_trailing_tokens_after_value_when_next_start_turn_then_ignore_them_unless_strict() {
+        let setup = Setup::new();
+        let input: Vec<&str> = setup
+            .game_config_input
+            .iter()
+            .map(|&l| {
+                if l == "loadtime 3000" {
+                    "loadtime 3000 extra"
+                } else {
+                    l
+                }
+            })
+            .collect();
+
+        let config = Parser::new(input.clone()).next_start_turn().unwrap();
+        let result = Parser::with_mode(input, ParseMode::Strict).next_start_turn();
+
+        assert_eq!(config.load_time, 3000);
+        assert_matches!(
+            result,
+            Err(Error {
+                kind: ErrorKind::CannotParseGameConfig,
+                ..
+            })
+        );
+    }
+
+    #[test]
+    fn given_duplicate_parameter_in_strict_mode_when_next_start_turn_then_return_error() {
+        let setup = Setup::new();
+        let input = setup.game_config_input.iter().flat_map(|&l| {
+            if l == "rows 30" {
+                vec![l, l]
+            } else {
+                vec![l]
+            }
+        });
+        let mut parser = Parser::with_mode(input, ParseMode::Strict);
+
+        let result = parser.next_start_turn().unwrap_err();
+
+        assert_eq!(result.kind, ErrorKind::CannotParseGameConfig);
+        assert_eq!(result.parameter, Some("rows".to_string()));
+        assert_eq!(result.line, 5);
+    }
+
+    #[test]
+    fn given_unknown_parameter_in_strict_mode_when_next_start_turn_then_return_error() {
+        let setup = Setup::new();
+        let input = setup
+            .game_config_input
+            .iter()
+            .flat_map(|&l| iter::once(l).chain(iter::once("INVALID_INPUT")));
+        let mut parser = Parser::with_mode(input, ParseMode::Strict);
+
+        let result = parser.next_start_turn().unwrap_err();
+
+        assert_eq!(result.text, Some("INVALID_INPUT".to_string()));
+        assert_eq!(result.line, 2);
+    }
+
+    #[test]
+    fn given_optional_and_unknown_parameters_when_next_start_turn_then_keep_them() {
+        let setup = Setup::new();
+        let mut input = setup.game_config_input;
+        let ready = input.pop().unwrap();
+        input.extend(vec![
+            "players 4",
+            "cutoff_percent 0.85",
+            "food_rate 5 11",
+            ready,
+        ]);
+        let mut parser = Parser::new(input);
+
+        let config = parser.next_start_turn().unwrap();
+
+        assert_eq!(config.players, Some(4));
+        assert_eq!(config.cutoff_percent, Some(0.85));
+        assert_eq!(config.cutoff_turn, None);
+        assert_eq!(config.extra.len(), 1);
+        assert_eq!(config.extra["food_rate"], "5 11");
+    }
+
+    #[test]
+    fn given_unknown_key_value_parameter_in_strict_mode_when_next_start_turn_then_keep_it() {
+        let setup = Setup::new();
+        let mut input = setup.game_config_input;
+        let ready = input.pop().unwrap();
+        input.extend(vec!["food_rate 5 11", ready]);
+        let mut parser = Parser::with_mode(input, ParseMode::Strict);
+
+        let config = parser.next_start_turn().unwrap();
+
+        assert_eq!(config.extra.len(), 1);
+        assert_eq!(config.extra["food_rate"], "5 11");
+    }
+
+    #[test]
+    fn given_invalid_optional_parameter_when_next_start_turn_then_ignore_it_unless_strict() {
+        let setup = Setup::new();
+        let mut input = setup.game_config_input;
+        let ready = input.pop().unwrap();
+        input.extend(vec!["players INVALID_VALUE", ready]);
+
+        let config = Parser::new(input.clone()).next_start_turn().unwrap();
+        let result = Parser::with_mode(input, ParseMode::Strict).next_start_turn();
+
+        assert_eq!(config.players, None);
+        assert_matches!(result, Err(Error { line: 11, .. }));
+    }
+
     fn create_player(id: i32, row: i32, col: i32) -> PlayerEntity {
         PlayerEntity {
             id,
@@ -367,7 +740,7 @@ mod tests {
             assert_eq!(turn_info.dead_ant[0], create_player(0, 14, 13));
             assert_eq!(turn_info.dead_ant[1], create_player(1, 15, 12));
         } else {
-            assert!(false);
+            panic!("unexpected result: {:?}", result);
         }
     }
 
@@ -385,6 +758,107 @@ mod tests {
         assert_matches!(result, Some(Turn::Normal(_)));
     }
 
+    fn a_strict_parser<'a>(
+        turn_info_input: impl IntoIterator<Item = &'a str>,
+    ) -> Parser<impl Iterator<Item = &'a str>> {
+        let setup = Setup::new();
+        let input = setup
+            .game_config_input
+            .into_iter()
+            .chain(iter::once("turn 1"))
+            .chain(turn_info_input);
+        let mut parser = Parser::with_mode(input, ParseMode::Strict);
+        parser.next_start_turn().unwrap();
+        parser
+    }
+
+    #[test]
+    fn given_correct_input_in_strict_mode_when_next_turn_then_return_turn_info() {
+        let setup = Setup::new();
+        let mut parser = a_strict_parser(setup.turn_info_input);
+
+        let result = parser.next_turn();
+
+        assert_matches!(result, Some(Turn::Normal(Ok(_))));
+    }
+
+    macro_rules! strict_turn_info_tests {
+    ($($name:ident: $line:expr, $parameter:expr,)*) => {
+    $(
+        #[test]
+        fn $name() {
+		let mut parser = a_strict_parser(vec!["f 7 4", $line, "go"]);
+
+		let result = parser.next_turn();
+
+		if let Some(Turn::Normal(Err(error))) = result {
+			assert_eq!(error.kind, ErrorKind::CannotParseTurnInfo);
+			assert_eq!(error.turn, 1);
+			assert_eq!(error.line, 14);
+			assert_eq!(error.text, Some($line.to_string()));
+			assert_eq!(error.parameter, Some($parameter.to_string()));
+		} else {
+			panic!("unexpected result: {:?}", result);
+		}
+        }
+    )*
+    }
+	}
+
+    strict_turn_info_tests! {
+        given_unknown_token_in_strict_mode_when_next_turn_then_return_error: "x 7 4", "x",
+        given_missing_player_id_in_strict_mode_when_next_turn_then_return_error: "a 7 4", "a",
+        given_negative_row_in_strict_mode_when_next_turn_then_return_error: "w -1 4", "row",
+        given_row_beyond_height_in_strict_mode_when_next_turn_then_return_error: "f 30 4", "row",
+        given_col_beyond_width_in_strict_mode_when_next_turn_then_return_error: "a 7 20 1", "col",
+        given_invalid_player_id_in_strict_mode_when_next_turn_then_return_error: "h 7 4 x", "id",
+    }
+
+    #[test]
+    fn given_turn_line_when_next_turn_then_remember_turn_number() {
+        let setup = Setup::new();
+        let input = iter::once("turn 7").chain(setup.turn_info_input);
+        let mut parser = Parser::new(input);
+        let before = Instant::now();
+
+        parser.next_turn();
+
+        assert_eq!(parser.turn(), 7);
+        assert!(parser.turn_started() >= before);
+    }
+
+    #[test]
+    fn given_invalid_turn_number_when_next_turn_then_skip_it() {
+        let setup = Setup::new();
+        let input = iter::once("turn INVALID_VALUE")
+            .chain(iter::once("turn 3"))
+            .chain(setup.turn_info_input);
+        let mut parser = Parser::new(input);
+
+        let result = parser.next_turn();
+
+        assert_matches!(result, Some(Turn::Normal(Ok(_))));
+        assert_eq!(parser.turn(), 3);
+    }
+
+    #[test]
+    fn given_invalid_turn_number_in_strict_mode_when_next_turn_then_return_error() {
+        let setup = Setup::new();
+        let input = iter::once("turn INVALID_VALUE").chain(setup.turn_info_input);
+        let mut parser = Parser::with_mode(input, ParseMode::Strict);
+
+        let result = parser.next_turn();
+
+        if let Some(Turn::Normal(Err(error))) = result {
+            assert_eq!(error.kind, ErrorKind::CannotParseTurnInfo);
+            assert_eq!(error.line, 1);
+            assert_eq!(error.text, Some("turn INVALID_VALUE".to_string()));
+            assert_eq!(error.parameter, Some("turn".to_string()));
+        } else {
+            panic!("unexpected result: {:?}", result);
+        }
+    }
+
     #[test]
     fn given_input_with_missing_go_when_next_turn_then_return_error() {
         let input = iter::once("turn 1");
@@ -392,7 +866,13 @@ mod tests {
 
         let result = parser.next_turn();
 
-        assert_matches!(result, Some(Turn::Normal(Err(Error::CannotParseTurnInfo))));
+        assert_matches!(
+            result,
+            Some(Turn::Normal(Err(Error {
+                kind: ErrorKind::CannotParseTurnInfo,
+                ..
+            })))
+        );
     }
 
     #[test]
@@ -424,7 +904,7 @@ mod tests {
             assert_eq!(turn_info.dead_ant[0], create_player(0, 14, 13));
             assert_eq!(turn_info.dead_ant[1], create_player(1, 15, 12));
         } else {
-            assert!(false);
+            panic!("unexpected result: {:?}", result);
         }
     }
 
@@ -438,7 +918,13 @@ mod tests {
 
         let result = parser.next_turn();
 
-        assert_matches!(result, Some(Turn::End(Err(Error::CannotParseEndInfo))));
+        assert_matches!(
+            result,
+            Some(Turn::End(Err(Error {
+                kind: ErrorKind::CannotParseEndInfo,
+                ..
+            })))
+        );
     }
 
     #[test]
@@ -451,7 +937,13 @@ mod tests {
 
         let result = parser.next_turn();
 
-        assert_matches!(result, Some(Turn::End(Err(Error::CannotParseEndInfo))));
+        assert_matches!(
+            result,
+            Some(Turn::End(Err(Error {
+                kind: ErrorKind::CannotParseEndInfo,
+                ..
+            })))
+        );
     }
 
     #[test]
@@ -464,7 +956,13 @@ mod tests {
 
         let result = parser.next_turn();
 
-        assert_matches!(result, Some(Turn::End(Err(Error::CannotParseEndInfo))));
+        assert_matches!(
+            result,
+            Some(Turn::End(Err(Error {
+                kind: ErrorKind::CannotParseEndInfo,
+                ..
+            })))
+        );
     }
 
     #[test]
@@ -477,7 +975,13 @@ mod tests {
 
         let result = parser.next_turn();
 
-        assert_matches!(result, Some(Turn::End(Err(Error::CannotParseEndInfo))));
+        assert_matches!(
+            result,
+            Some(Turn::End(Err(Error {
+                kind: ErrorKind::CannotParseEndInfo,
+                ..
+            })))
+        );
     }
 
     #[test]
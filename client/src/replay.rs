@@ -0,0 +1,313 @@
+use std::fmt;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::BufWriter;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    InvalidLine { line: usize, text: String },
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "cannot read replay: {}", error),
+            ReplayError::InvalidLine { line, text } => {
+                write!(f, "invalid replay entry at line {}: '{}'", line, text)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    Input(String),
+    Output(String),
+    TurnTime { turn: i32, compute: Duration },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub elapsed: Duration,
+    pub event: Event,
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ", self.elapsed.as_micros())?;
+        match &self.event {
+            Event::Input(line) => write!(f, "< {}", line),
+            Event::Output(line) => write!(f, "> {}", line),
+            Event::TurnTime { turn, compute } => write!(f, "t {} {}", turn, compute.as_micros()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub entries: Vec<Entry>,
+}
+
+impl Replay {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, ReplayError> {
+        let entries = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| {
+                parse_entry(line).ok_or_else(|| ReplayError::InvalidLine {
+                    line: i + 1,
+                    text: line.to_string(),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { entries })
+    }
+
+    pub fn input(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().filter_map(|e| match &e.event {
+            Event::Input(line) => Some(line.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn output(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().filter_map(|e| match &e.event {
+            Event::Output(line) => Some(line.as_str()),
+            _ => None,
+        })
+    }
+
+    pub fn turn_times(&self) -> impl Iterator<Item = (i32, Duration)> + '_ {
+        self.entries.iter().filter_map(|e| match e.event {
+            Event::TurnTime { turn, compute } => Some((turn, compute)),
+            _ => None,
+        })
+    }
+}
+
+fn parse_entry(line: &str) -> Option<Entry> {
+    let mut parts = line.splitn(3, ' ');
+    let elapsed = parse_micros(parts.next()?)?;
+    let (kind, text) = (parts.next()?, parts.next().unwrap_or(""));
+    let event = match kind {
+        "<" => Event::Input(text.to_string()),
+        ">" => Event::Output(text.to_string()),
+        "t" => {
+            let mut t = text.split(' ');
+            let (turn, compute) = (t.next()?.parse().ok()?, parse_micros(t.next()?)?);
+            if t.next().is_some() {
+                return None;
+            }
+            Event::TurnTime { turn, compute }
+        }
+        _ => return None,
+    };
+    Some(Entry { elapsed, event })
+}
+
+fn parse_micros(value: &str) -> Option<Duration> {
+    value.parse().ok().map(Duration::from_micros)
+}
+
+struct Recording {
+    writer: Box<dyn io::Write + Send>,
+    started: Instant,
+    error: Option<io::Error>,
+}
+
+#[derive(Clone)]
+pub struct Recorder {
+    recording: Arc<Mutex<Recording>>,
+}
+
+impl fmt::Debug for Recorder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Recorder").finish()
+    }
+}
+
+impl Recorder {
+    pub fn new(writer: impl io::Write + Send + 'static) -> Self {
+        Self {
+            recording: Arc::new(Mutex::new(Recording {
+                writer: Box::new(writer),
+                started: Instant::now(),
+                error: None,
+            })),
+        }
+    }
+
+    pub fn create(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?)))
+    }
+
+    pub fn input(&self, line: &str) {
+        self.record(Event::Input(line.to_string()));
+    }
+
+    pub fn output(&self, line: &str) {
+        self.record(Event::Output(line.to_string()));
+    }
+
+    pub fn turn_time(&self, turn: i32, compute: Duration) {
+        self.record(Event::TurnTime { turn, compute });
+        self.with_recording(|r| r.writer.flush());
+    }
+
+    pub fn finish(&self) -> io::Result<()> {
+        self.with_recording(|r| r.writer.flush());
+        self.recording
+            .lock()
+            .ok()
+            .and_then(|mut r| r.error.take())
+            .map_or(Ok(()), Err)
+    }
+
+    fn record(&self, event: Event) {
+        self.with_recording(|r| {
+            let entry = Entry {
+                elapsed: r.started.elapsed(),
+                event,
+            };
+            writeln!(r.writer, "{}", entry)
+        });
+    }
+
+    fn with_recording(&self, write: impl FnOnce(&mut Recording) -> io::Result<()>) {
+        if let Ok(mut recording) = self.recording.lock() {
+            if recording.error.is_none() {
+                if let Err(error) = write(&mut recording) {
+                    recording.error = Some(error);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    struct BrokenPipe;
+
+    impl io::Write for BrokenPipe {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn given_recorded_events_when_parse_then_return_same_events() {
+        let buffer = SharedBuffer::default();
+        let recorder = Recorder::new(buffer.clone());
+
+        recorder.input("turn 1");
+        recorder.input("");
+        recorder.output("o 1 2 N");
+        recorder.turn_time(1, Duration::from_micros(1500));
+        recorder.finish().unwrap();
+        let replay = Replay::parse(&buffer.text()).unwrap();
+
+        assert_eq!(replay.input().collect::<Vec<_>>(), vec!["turn 1", ""]);
+        assert_eq!(replay.output().collect::<Vec<_>>(), vec!["o 1 2 N"]);
+        assert_eq!(
+            replay.turn_times().collect::<Vec<_>>(),
+            vec![(1, Duration::from_micros(1500))]
+        );
+        assert!(replay
+            .entries
+            .windows(2)
+            .all(|w| w[0].elapsed <= w[1].elapsed));
+    }
+
+    #[test]
+    fn given_replay_when_written_and_parsed_then_return_same_replay() {
+        let text = "0 < turn 0\n12 < ready\n40 > go\n41 t 0 28\n";
+
+        let replay = Replay::parse(text).unwrap();
+
+        assert_eq!(
+            replay.entries[3],
+            Entry {
+                elapsed: Duration::from_micros(41),
+                event: Event::TurnTime {
+                    turn: 0,
+                    compute: Duration::from_micros(28)
+                }
+            }
+        );
+        let written: String = replay.entries.iter().map(|e| format!("{}\n", e)).collect();
+        assert_eq!(written, text);
+    }
+
+    #[test]
+    fn given_invalid_entry_when_parse_then_return_error() {
+        let kind = Replay::parse("0 < turn 0\n5 ? go\n");
+        let time = Replay::parse("x < turn 0\n");
+        let turn_time = Replay::parse("5 t 1\n");
+
+        assert_matches!(kind, Err(ReplayError::InvalidLine { line: 2, .. }));
+        assert_matches!(time, Err(ReplayError::InvalidLine { line: 1, .. }));
+        assert_matches!(turn_time, Err(ReplayError::InvalidLine { line: 1, .. }));
+    }
+
+    #[test]
+    fn given_broken_writer_when_finish_then_return_first_error() {
+        let recorder = Recorder::new(BrokenPipe);
+
+        recorder.input("turn 0");
+        recorder.output("go");
+
+        assert_matches!(recorder.finish(), Err(e) if e.kind() == io::ErrorKind::BrokenPipe);
+        assert_matches!(recorder.finish(), Ok(()));
+    }
+
+    #[test]
+    fn given_missing_file_when_load_then_return_io_error() {
+        let path = std::env::temp_dir().join(format!("ants-missing-{}.replay", std::process::id()));
+
+        assert_matches!(Replay::load(&path), Err(ReplayError::Io(_)));
+    }
+}
@@ -1,33 +1,72 @@
+use crate::replay::Recorder;
 use ai::Direction;
 use ai::Order;
+use std::io;
+
+pub trait Output {
+    fn write_str(&mut self, text: &str) -> io::Result<()>;
+    fn flush(&mut self) -> io::Result<()>;
+}
+
+impl<W: io::Write> Output for W {
+    fn write_str(&mut self, text: &str) -> io::Result<()> {
+        self.write_all(text.as_bytes())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::Write::flush(self)
+    }
+}
 
 #[derive(Debug)]
-pub struct Unparser<F> {
-    output: F,
+pub struct Unparser<O> {
+    output: O,
+    buffer: String,
+    recorder: Option<Recorder>,
 }
 
-impl<F: Fn(&str)> Unparser<F> {
-    pub fn new(output: F) -> Self {
-        Self { output }
+impl<O: Output> Unparser<O> {
+    pub fn new(output: O) -> Self {
+        Self {
+            output,
+            buffer: String::new(),
+            recorder: None,
+        }
+    }
+
+    pub fn record(&mut self, recorder: Recorder) {
+        self.recorder = Some(recorder);
     }
 
-    pub fn output_go(&self) {
-        (self.output)("go");
+    pub fn output_go(&mut self) -> io::Result<()> {
+        self.write_line("go");
+        let written = self.output.write_str(&self.buffer);
+        self.buffer.clear();
+        written?;
+        self.output.flush()
     }
 
-    pub fn output_orders(&self, orders: Vec<Order>) {
-        orders.iter().for_each(|o| {
-            (self.output)(
+    pub fn output_orders(&mut self, orders: Vec<Order>) -> io::Result<()> {
+        for o in orders {
+            self.write_line(
                 format!(
                     "o {} {} {}",
-                    o.pos.y.to_string(),
-                    o.pos.x.to_string(),
+                    o.pos.y,
+                    o.pos.x,
                     unparse_direction(o.direction),
                 )
                 .as_str(),
             );
-        });
-        (self.output)("go");
+        }
+        self.output_go()
+    }
+
+    fn write_line(&mut self, line: &str) {
+        if let Some(recorder) = &self.recorder {
+            recorder.output(line);
+        }
+        self.buffer.push_str(line);
+        self.buffer.push('\n');
     }
 }
 
@@ -44,18 +83,46 @@ fn unparse_direction(direction: Direction) -> char {
 mod tests {
     use super::*;
     use ai::Position;
-    use std::cell::RefCell;
+    use std::io::Write;
+
+    struct FlushCounter {
+        written: Vec<u8>,
+        writes: usize,
+        flushes: Vec<usize>,
+    }
+
+    impl Write for FlushCounter {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.writes += 1;
+            self.written.write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            self.flushes.push(self.written.len());
+            Ok(())
+        }
+    }
+
+    struct BrokenPipe;
+
+    impl Write for BrokenPipe {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::from(io::ErrorKind::BrokenPipe))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
 
     #[test]
     fn when_output_go_then_output_go() {
-        let outputs = RefCell::new(vec![]);
-        let save_output = |o: &str| outputs.borrow_mut().push(o.to_string());
-        let unparser = Unparser::new(save_output);
+        let mut output = vec![];
+        let mut unparser = Unparser::new(&mut output);
 
-        unparser.output_go();
+        unparser.output_go().unwrap();
 
-        let outputs = outputs.borrow();
-        assert_eq!(outputs[0].as_str(), "go");
+        assert_eq!(output, b"go\n");
     }
 
     #[test]
@@ -78,17 +145,52 @@ mod tests {
                 direction: Direction::W,
             },
         ];
-        let outputs = RefCell::new(vec![]);
-        let save_output = |o: &str| outputs.borrow_mut().push(o.to_string());
-        let unparser = Unparser::new(save_output);
-
-        unparser.output_orders(orders);
-
-        let outputs = outputs.borrow();
-        assert_eq!(outputs[0].as_str(), "o 0 0 N");
-        assert_eq!(outputs[1].as_str(), "o 0 1 E");
-        assert_eq!(outputs[2].as_str(), "o 1 0 S");
-        assert_eq!(outputs[3].as_str(), "o 42 32 W");
-        assert_eq!(outputs[4].as_str(), "go");
+        let mut output = vec![];
+        let mut unparser = Unparser::new(&mut output);
+
+        unparser.output_orders(orders).unwrap();
+
+        let output = String::from_utf8(output).unwrap();
+        let outputs: Vec<&str> = output.lines().collect();
+        assert_eq!(outputs[0], "o 0 0 N");
+        assert_eq!(outputs[1], "o 0 1 E");
+        assert_eq!(outputs[2], "o 1 0 S");
+        assert_eq!(outputs[3], "o 42 32 W");
+        assert_eq!(outputs[4], "go");
+    }
+
+    #[test]
+    fn given_orders_when_output_orders_then_write_and_flush_once_after_go() {
+        let mut output = FlushCounter {
+            written: vec![],
+            writes: 0,
+            flushes: vec![],
+        };
+        let mut unparser = Unparser::new(&mut output);
+
+        unparser
+            .output_orders(vec![Order::new(1, 2, Direction::N); 300])
+            .unwrap();
+
+        assert_eq!(output.writes, 1);
+        assert_eq!(output.flushes, vec![output.written.len()]);
+        assert!(output.written.ends_with(b"go\n"));
+    }
+
+    #[test]
+    fn given_broken_output_when_output_orders_then_return_error() {
+        let mut unparser = Unparser::new(BrokenPipe);
+
+        let result = unparser.output_orders(vec![Order::new(1, 2, Direction::N)]);
+
+        assert_eq!(result.unwrap_err().kind(), io::ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn given_failed_turn_when_output_go_then_do_not_resend_failed_orders() {
+        let mut unparser = Unparser::new(BrokenPipe);
+        let _ = unparser.output_orders(vec![Order::new(1, 2, Direction::N)]);
+
+        assert!(unparser.buffer.is_empty());
     }
 }
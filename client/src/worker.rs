@@ -0,0 +1,160 @@
+use ai::EndInfo;
+use ai::Order;
+use ai::TurnContext;
+use ai::TurnInfo;
+use ai::TurnTaker;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::Sender;
+use std::thread;
+use std::time::Instant;
+
+enum Job {
+    Turn(TurnInfo, TurnContext),
+    End(EndInfo),
+}
+
+pub struct Worker {
+    jobs: Sender<Job>,
+    results: Receiver<(i32, Vec<Order>)>,
+    pending: usize,
+}
+
+impl Worker {
+    pub fn spawn(mut turn_taker: impl TurnTaker + Send + 'static) -> Self {
+        let (jobs, job_receiver) = mpsc::channel();
+        let (result_sender, results) = mpsc::channel();
+        thread::spawn(move || {
+            for job in job_receiver {
+                match job {
+                    Job::Turn(turn_info, context) => {
+                        let orders = turn_taker.take_turn(turn_info, &context);
+                        let _ = result_sender.send((context.turn, orders));
+                    }
+                    Job::End(end_info) => {
+                        turn_taker.end(end_info);
+                        let _ = result_sender.send((-1, vec![]));
+                        return;
+                    }
+                }
+            }
+        });
+        Self {
+            jobs,
+            results,
+            pending: 0,
+        }
+    }
+
+    pub fn take_turn(
+        &mut self,
+        turn_info: TurnInfo,
+        context: &TurnContext,
+        cutoff: Option<Instant>,
+    ) -> Vec<Order> {
+        if self
+            .jobs
+            .send(Job::Turn(turn_info, context.clone()))
+            .is_err()
+        {
+            return vec![];
+        }
+        self.pending += 1;
+        loop {
+            let result = match cutoff {
+                Some(cutoff) => self
+                    .results
+                    .recv_timeout(cutoff.saturating_duration_since(Instant::now()))
+                    .ok(),
+                None => self.results.recv().ok(),
+            };
+            match result {
+                Some((turn, orders)) => {
+                    self.pending -= 1;
+                    if turn == context.turn {
+                        return orders;
+                    }
+                }
+                None => return context.draft.take(),
+            }
+        }
+    }
+
+    pub fn end(self, end_info: EndInfo) {
+        if self.jobs.send(Job::End(end_info)).is_ok() && self.pending == 0 {
+            let _ = self.results.recv();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ai::Direction;
+    use ai::OrderDraft;
+    use std::sync::Arc;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    struct BlockedTurnTaker {
+        ended: Arc<Mutex<bool>>,
+    }
+
+    impl TurnTaker for BlockedTurnTaker {
+        fn take_turn(&mut self, _turn_info: TurnInfo, context: &TurnContext) -> Vec<Order> {
+            context.draft.publish(vec![Order::new(1, 2, Direction::N)]);
+            loop {
+                thread::park();
+            }
+        }
+
+        fn end(&mut self, _end_info: EndInfo) {
+            *self.ended.lock().unwrap() = true;
+        }
+    }
+
+    fn a_turn_info() -> TurnInfo {
+        TurnInfo {
+            water: vec![],
+            food: vec![],
+            ant_hill: vec![],
+            ant: vec![],
+            dead_ant: vec![],
+        }
+    }
+
+    fn a_context(turn: i32) -> TurnContext {
+        TurnContext {
+            turn,
+            turns: 500,
+            deadline: None,
+            draft: OrderDraft::new(),
+        }
+    }
+
+    fn an_end_info() -> EndInfo {
+        EndInfo {
+            scores: vec![1, 1],
+            turn_info: a_turn_info(),
+        }
+    }
+
+    #[test]
+    fn given_blocked_turn_taker_when_take_turns_and_end_then_never_wait_for_it() {
+        let ended = Arc::new(Mutex::new(false));
+        let mut worker = Worker::spawn(BlockedTurnTaker {
+            ended: ended.clone(),
+        });
+        let started = Instant::now();
+        let cutoff = || Some(Instant::now() + Duration::from_millis(50));
+
+        let first = worker.take_turn(a_turn_info(), &a_context(1), cutoff());
+        let second = worker.take_turn(a_turn_info(), &a_context(2), cutoff());
+        worker.end(an_end_info());
+
+        assert_eq!(first, vec![Order::new(1, 2, Direction::N)]);
+        assert!(second.is_empty());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(!*ended.lock().unwrap());
+    }
+}
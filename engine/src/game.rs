@@ -0,0 +1,751 @@
+use crate::Map;
+use ai::CombatSimulator;
+use ai::Config;
+use ai::Direction;
+use ai::EndInfo;
+use ai::Geometry;
+use ai::Order;
+use ai::OrderDraft;
+use ai::PlayerEntity;
+use ai::Position;
+use ai::TurnContext;
+use ai::TurnInfo;
+use ai::TurnTaker;
+use ai::Visibility;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+use std::time::Instant;
+
+const FOOD_PER_PLAYER_PER_TURN: f64 = 0.25;
+const FOOD_PLACEMENT_ATTEMPTS: usize = 100;
+const RAZE_POINTS: i32 = 2;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GameError {
+    NoPlayers,
+    InvalidPlayerId { id: i32 },
+    WrongTurnTakerCount { expected: usize, found: usize },
+}
+
+impl fmt::Display for GameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GameError::NoPlayers => write!(f, "cannot play a map without players"),
+            GameError::InvalidPlayerId { id } => write!(
+                f,
+                "invalid player id {}, expected 0 to {}",
+                id,
+                Map::MAX_PLAYERS - 1
+            ),
+            GameError::WrongTurnTakerCount { expected, found } => write!(
+                f,
+                "expected one turn taker per player ({}), found {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GameError {}
+
+#[derive(Debug)]
+pub struct Game {
+    config: Config,
+    geometry: Geometry,
+    combat: CombatSimulator,
+    rng: StdRng,
+    players: usize,
+    turn: i32,
+    water: Vec<bool>,
+    food: Vec<Position>,
+    hills: Vec<PlayerEntity>,
+    ants: Vec<PlayerEntity>,
+    dead_ants: Vec<PlayerEntity>,
+    moves: HashMap<Position, Direction>,
+    stock: Vec<i32>,
+    scores: Vec<i32>,
+    sent_water: Vec<Vec<bool>>,
+    timed_out: Vec<bool>,
+    food_due: f64,
+}
+
+impl Game {
+    pub fn new(map: &Map, config: Config, seed: u64) -> Result<Self, GameError> {
+        if let Some(entity) = map
+            .hills
+            .iter()
+            .chain(map.ants.iter())
+            .find(|e| e.id < 0 || e.id as usize >= Map::MAX_PLAYERS)
+        {
+            return Err(GameError::InvalidPlayerId { id: entity.id });
+        }
+        let players = map.players();
+        if players == 0 {
+            return Err(GameError::NoPlayers);
+        }
+        let config = Config {
+            width: map.width,
+            height: map.height,
+            players: Some(players as i32),
+            ..config
+        };
+        let geometry = Geometry::from_config(&config);
+        let wrap = |e: &PlayerEntity| PlayerEntity {
+            id: e.id,
+            pos: geometry.wrap(e.pos),
+        };
+
+        let mut water = vec![false; geometry.size()];
+        for &pos in &map.water {
+            water[geometry.index(pos)] = true;
+        }
+        let hills: Vec<PlayerEntity> = map.hills.iter().map(wrap).collect();
+        let mut ants: Vec<PlayerEntity> = map.ants.iter().map(wrap).collect();
+        for hill in &hills {
+            if ants.iter().all(|a| a.pos != hill.pos) {
+                ants.push(*hill);
+            }
+        }
+        let mut scores = vec![0; players];
+        for hill in &hills {
+            scores[hill.id as usize] += 1;
+        }
+
+        Ok(Self {
+            combat: CombatSimulator::from_config(&config),
+            config,
+            geometry,
+            rng: StdRng::seed_from_u64(seed),
+            players,
+            turn: 1,
+            water,
+            food: map.food.iter().map(|&f| geometry.wrap(f)).collect(),
+            hills,
+            ants,
+            dead_ants: vec![],
+            moves: HashMap::new(),
+            stock: vec![0; players],
+            scores,
+            sent_water: vec![vec![false; geometry.size()]; players],
+            timed_out: vec![false; players],
+            food_due: 0.0,
+        })
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    pub fn turn(&self) -> i32 {
+        self.turn
+    }
+
+    pub fn scores(&self) -> &[i32] {
+        &self.scores
+    }
+
+    pub fn food(&self) -> &[Position] {
+        &self.food
+    }
+
+    pub fn hills(&self) -> &[PlayerEntity] {
+        &self.hills
+    }
+
+    pub fn ants(&self) -> &[PlayerEntity] {
+        &self.ants
+    }
+
+    pub fn dead_ants(&self) -> &[PlayerEntity] {
+        &self.dead_ants
+    }
+
+    pub fn is_water(&self, pos: Position) -> bool {
+        self.water[self.geometry.index(pos)]
+    }
+
+    pub fn is_alive(&self, player: usize) -> bool {
+        let id = player as i32;
+        self.hills.iter().any(|h| h.id == id) || self.ants.iter().any(|a| a.id == id)
+    }
+
+    pub fn is_timed_out(&self, player: usize) -> bool {
+        self.timed_out[player]
+    }
+
+    pub fn is_over(&self) -> bool {
+        let alive = (0..self.players).filter(|&p| self.is_alive(p)).count();
+        self.turn > self.config.turns || alive == 0 || (self.players > 1 && alive == 1)
+    }
+
+    pub fn turn_info(&mut self, player: usize) -> TurnInfo {
+        let geometry = self.geometry;
+        let mut visibility = Visibility::new(geometry, self.config.view_radius2);
+        visibility.update(
+            self.ants
+                .iter()
+                .filter(|a| a.id == player as i32)
+                .map(|a| a.pos),
+        );
+
+        let water_map = &self.water;
+        let sent = &mut self.sent_water[player];
+        let water = visibility
+            .visible_positions()
+            .filter(|&pos| {
+                let i = geometry.index(pos);
+                water_map[i] && !std::mem::replace(&mut sent[i], true)
+            })
+            .collect();
+        let players = self.players as i32;
+        let visible = |entities: &[PlayerEntity]| -> Vec<PlayerEntity> {
+            entities
+                .iter()
+                .filter(|e| visibility.is_visible(e.pos))
+                .map(|e| PlayerEntity {
+                    id: (e.id - player as i32).rem_euclid(players),
+                    pos: e.pos,
+                })
+                .collect()
+        };
+
+        TurnInfo {
+            water,
+            food: self
+                .food
+                .iter()
+                .copied()
+                .filter(|&f| visibility.is_visible(f))
+                .collect(),
+            ant_hill: visible(&self.hills),
+            ant: visible(&self.ants),
+            dead_ant: visible(&self.dead_ants),
+        }
+    }
+
+    pub fn order(&mut self, player: usize, orders: Vec<Order>) {
+        for order in orders {
+            let pos = self.geometry.wrap(order.pos);
+            let own = self
+                .ants
+                .iter()
+                .any(|a| a.id == player as i32 && a.pos == pos);
+            if own {
+                self.moves.entry(pos).or_insert(order.direction);
+            }
+        }
+    }
+
+    pub fn finish_turn(&mut self) {
+        self.dead_ants.clear();
+        self.move_ants();
+        self.attack();
+        self.raze_hills();
+        self.spawn_ants();
+        self.gather_food();
+        self.spawn_food();
+        self.turn += 1;
+    }
+
+    pub fn end_info(&mut self, player: usize) -> EndInfo {
+        let scores = (0..self.players)
+            .map(|i| self.scores[(player + i) % self.players])
+            .collect();
+        EndInfo {
+            scores,
+            turn_info: self.turn_info(player),
+        }
+    }
+
+    pub fn play(&mut self, turn_takers: &mut [&mut dyn TurnTaker]) -> Result<Vec<i32>, GameError> {
+        if turn_takers.len() != self.players {
+            return Err(GameError::WrongTurnTakerCount {
+                expected: self.players,
+                found: turn_takers.len(),
+            });
+        }
+        let turn_time = Duration::from_millis(self.config.turn_time.max(0) as u64);
+        for turn_taker in turn_takers.iter_mut() {
+            turn_taker.setup(&self.config);
+        }
+        while !self.is_over() {
+            for (player, turn_taker) in turn_takers.iter_mut().enumerate() {
+                if !self.is_alive(player) || self.timed_out[player] {
+                    continue;
+                }
+                let turn_info = self.turn_info(player);
+                let deadline = Instant::now() + turn_time;
+                let context = TurnContext {
+                    turn: self.turn,
+                    turns: self.config.turns,
+                    deadline: Some(deadline),
+                    draft: OrderDraft::new(),
+                };
+                let orders = turn_taker.take_turn(turn_info, &context);
+                if Instant::now() > deadline {
+                    self.timed_out[player] = true;
+                } else {
+                    self.order(player, orders);
+                }
+            }
+            self.finish_turn();
+        }
+        for (player, turn_taker) in turn_takers.iter_mut().enumerate() {
+            if !self.timed_out[player] {
+                turn_taker.end(self.end_info(player));
+            }
+        }
+        Ok(self.scores.clone())
+    }
+
+    fn move_ants(&mut self) {
+        let moves = std::mem::take(&mut self.moves);
+        let ants: Vec<PlayerEntity> = std::mem::take(&mut self.ants)
+            .into_iter()
+            .map(|a| match moves.get(&a.pos) {
+                Some(&direction) => {
+                    let pos = self.geometry.step(a.pos, direction);
+                    if self.is_water(pos) || self.food.contains(&pos) {
+                        a
+                    } else {
+                        PlayerEntity { pos, ..a }
+                    }
+                }
+                None => a,
+            })
+            .collect();
+
+        let mut occupants: HashMap<Position, usize> = HashMap::new();
+        for ant in &ants {
+            *occupants.entry(ant.pos).or_insert(0) += 1;
+        }
+        let (ants, collided): (Vec<PlayerEntity>, Vec<PlayerEntity>) =
+            ants.into_iter().partition(|a| occupants[&a.pos] == 1);
+        self.ants = ants;
+        self.dead_ants.extend(collided);
+    }
+
+    fn attack(&mut self) {
+        let dies = self.combat.dies(&self.ants);
+        let (dead, ants): (Vec<_>, Vec<_>) = std::mem::take(&mut self.ants)
+            .into_iter()
+            .zip(dies)
+            .partition(|&(_, dies)| dies);
+        self.ants = ants.into_iter().map(|(a, _)| a).collect();
+        self.dead_ants.extend(dead.into_iter().map(|(a, _)| a));
+    }
+
+    fn raze_hills(&mut self) {
+        let ants = &self.ants;
+        let mut razed = vec![];
+        self.hills.retain(
+            |h| match ants.iter().find(|a| a.pos == h.pos && a.id != h.id) {
+                Some(a) => {
+                    razed.push((a.id, h.id));
+                    false
+                }
+                None => true,
+            },
+        );
+        for (razer, owner) in razed {
+            self.scores[razer as usize] += RAZE_POINTS;
+            self.scores[owner as usize] -= 1;
+        }
+    }
+
+    fn spawn_ants(&mut self) {
+        for i in 0..self.hills.len() {
+            let hill = self.hills[i];
+            let player = hill.id as usize;
+            if self.stock[player] > 0 && self.ants.iter().all(|a| a.pos != hill.pos) {
+                self.stock[player] -= 1;
+                self.ants.push(hill);
+            }
+        }
+    }
+
+    fn gather_food(&mut self) {
+        let geometry = self.geometry;
+        let radius2 = self.config.food_gathering_radius2;
+        let ants = &self.ants;
+        let stock = &mut self.stock;
+        self.food.retain(|&food| {
+            let mut gatherers: Vec<i32> = ants
+                .iter()
+                .filter(|a| geometry.distance2(a.pos, food) <= radius2)
+                .map(|a| a.id)
+                .collect();
+            gatherers.sort_unstable();
+            gatherers.dedup();
+            match gatherers.as_slice() {
+                [] => true,
+                [player] => {
+                    stock[*player as usize] += 1;
+                    false
+                }
+                _ => false,
+            }
+        });
+    }
+
+    fn spawn_food(&mut self) {
+        let alive = (0..self.players).filter(|&p| self.is_alive(p)).count();
+        self.food_due += FOOD_PER_PLAYER_PER_TURN * alive as f64;
+        while self.food_due >= 1.0 {
+            self.food_due -= 1.0;
+            if let Some(pos) = self.free_land() {
+                self.food.push(pos);
+            }
+        }
+    }
+
+    fn free_land(&mut self) -> Option<Position> {
+        for _ in 0..FOOD_PLACEMENT_ATTEMPTS {
+            let pos = self
+                .geometry
+                .position(self.rng.gen_range(0, self.geometry.size()));
+            if self.is_free(pos) {
+                return Some(pos);
+            }
+        }
+        None
+    }
+
+    fn is_free(&self, pos: Position) -> bool {
+        !self.is_water(pos)
+            && !self.food.contains(&pos)
+            && self.hills.iter().all(|h| h.pos != pos)
+            && self.ants.iter().all(|a| a.pos != pos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::a_config;
+    use crate::test_support::entity;
+    use crate::test_support::pos;
+    use std::thread;
+
+    struct ScriptedTurnTaker {
+        direction: Direction,
+        turns: i32,
+        delay: Duration,
+        calls: i32,
+        ended: bool,
+    }
+
+    impl ScriptedTurnTaker {
+        fn new(direction: Direction, turns: i32) -> Self {
+            Self {
+                direction,
+                turns,
+                delay: Duration::from_millis(0),
+                calls: 0,
+                ended: false,
+            }
+        }
+    }
+
+    impl TurnTaker for ScriptedTurnTaker {
+        fn take_turn(&mut self, turn_info: TurnInfo, context: &TurnContext) -> Vec<Order> {
+            self.calls += 1;
+            thread::sleep(self.delay);
+            if context.turn > self.turns {
+                return vec![];
+            }
+            turn_info
+                .ant
+                .iter()
+                .filter(|a| a.is_own())
+                .map(|a| Order {
+                    pos: a.pos,
+                    direction: self.direction,
+                })
+                .collect()
+        }
+
+        fn end(&mut self, _end_info: EndInfo) {
+            self.ended = true;
+        }
+    }
+
+    fn a_game_config() -> Config {
+        Config {
+            turn_time: 100,
+            ..a_config()
+        }
+    }
+
+    fn a_map(hills: Vec<PlayerEntity>) -> Map {
+        let mut map = Map::new(20, 20);
+        map.hills = hills;
+        map
+    }
+
+    #[test]
+    fn given_map_when_new_then_spawn_an_ant_on_every_hill() {
+        let game = Game::new(
+            &a_map(vec![entity(0, 2, 2), entity(1, 15, 15)]),
+            a_game_config(),
+            1,
+        )
+        .unwrap();
+
+        assert_eq!(game.players(), 2);
+        assert_eq!(game.ants(), &[entity(0, 2, 2), entity(1, 15, 15)][..]);
+        assert_eq!(game.scores(), &[1, 1][..]);
+        assert_eq!(game.turn(), 1);
+    }
+
+    #[test]
+    fn given_order_when_finish_turn_then_move_ant() {
+        let mut game = Game::new(&a_map(vec![entity(0, 2, 2)]), a_game_config(), 1).unwrap();
+
+        game.order(0, vec![Order::new(2, 2, Direction::E)]);
+        game.finish_turn();
+
+        assert_eq!(game.ants(), &[entity(0, 3, 2)][..]);
+        assert_eq!(game.turn(), 2);
+    }
+
+    #[test]
+    fn given_order_into_water_or_for_missing_ant_when_finish_turn_then_ignore_order() {
+        let mut map = a_map(vec![entity(0, 2, 2)]);
+        map.water.push(pos(2, 1));
+        let mut game = Game::new(&map, a_game_config(), 1).unwrap();
+
+        game.order(
+            0,
+            vec![
+                Order::new(2, 2, Direction::N),
+                Order::new(5, 5, Direction::S),
+            ],
+        );
+        game.finish_turn();
+
+        assert_eq!(game.ants(), &[entity(0, 2, 2)][..]);
+    }
+
+    #[test]
+    fn given_two_ants_moving_onto_same_tile_when_finish_turn_then_both_die() {
+        let mut map = a_map(vec![entity(0, 2, 2)]);
+        map.ants = vec![entity(0, 2, 2), entity(0, 4, 2)];
+        let mut game = Game::new(&map, a_game_config(), 1).unwrap();
+
+        game.order(
+            0,
+            vec![
+                Order::new(2, 2, Direction::E),
+                Order::new(4, 2, Direction::W),
+            ],
+        );
+        game.finish_turn();
+
+        assert!(game.ants().is_empty());
+        assert_eq!(game.dead_ants(), &[entity(0, 3, 2), entity(0, 3, 2)][..]);
+    }
+
+    #[test]
+    fn given_outnumbered_enemy_in_attack_range_when_finish_turn_then_enemy_dies() {
+        let mut map = a_map(vec![entity(0, 1, 1), entity(1, 18, 18)]);
+        map.ants = vec![entity(0, 5, 5), entity(0, 5, 6), entity(1, 7, 5)];
+        let mut game = Game::new(&map, a_game_config(), 1).unwrap();
+
+        game.finish_turn();
+
+        assert!(game.ants().iter().all(|a| a.pos != pos(7, 5)));
+        assert!(game.ants().contains(&entity(0, 5, 5)));
+        assert!(game.ants().contains(&entity(0, 5, 6)));
+    }
+
+    #[test]
+    fn given_enemy_ant_on_hill_when_finish_turn_then_raze_hill_and_score() {
+        let mut map = a_map(vec![entity(0, 1, 1), entity(1, 10, 10)]);
+        map.ants = vec![entity(0, 1, 1), entity(0, 10, 10), entity(1, 18, 18)];
+        let mut game = Game::new(&map, a_game_config(), 1).unwrap();
+
+        game.finish_turn();
+
+        assert_eq!(game.hills(), &[entity(0, 1, 1)][..]);
+        assert_eq!(game.scores(), &[3, 0][..]);
+    }
+
+    #[test]
+    fn given_food_next_to_single_player_when_finish_turn_then_gather_and_spawn_ant() {
+        let mut map = a_map(vec![entity(0, 5, 5)]);
+        map.food.push(pos(5, 7));
+        let mut game = Game::new(&map, a_game_config(), 1).unwrap();
+
+        game.order(0, vec![Order::new(5, 5, Direction::S)]);
+        game.finish_turn();
+        let gathered = !game.food().contains(&pos(5, 7));
+        game.finish_turn();
+
+        assert!(gathered);
+        assert_eq!(game.ants(), &[entity(0, 5, 6), entity(0, 5, 5)][..]);
+    }
+
+    #[test]
+    fn given_food_between_two_players_when_finish_turn_then_destroy_food() {
+        let mut map = a_map(vec![entity(0, 1, 1), entity(1, 18, 18)]);
+        map.ants = vec![entity(0, 10, 4), entity(1, 10, 6)];
+        map.food.push(pos(10, 5));
+        let config = Config {
+            attack_radius2: 2,
+            ..a_game_config()
+        };
+        let mut game = Game::new(&map, config, 1).unwrap();
+
+        game.finish_turn();
+        game.finish_turn();
+
+        assert!(!game.food().contains(&pos(10, 5)));
+        assert_eq!(game.ants().len(), 4);
+    }
+
+    #[test]
+    fn given_two_players_when_turn_info_then_show_visible_entities_relative_to_player() {
+        let mut map = a_map(vec![entity(0, 5, 5), entity(1, 9, 5)]);
+        map.food = vec![pos(5, 15), pos(9, 7)];
+        let mut game = Game::new(&map, a_game_config(), 1).unwrap();
+
+        let turn_info = game.turn_info(1);
+
+        assert_eq!(turn_info.ant, vec![entity(1, 5, 5), entity(0, 9, 5)]);
+        assert_eq!(turn_info.ant_hill, vec![entity(1, 5, 5), entity(0, 9, 5)]);
+        assert_eq!(turn_info.food, vec![pos(9, 7)]);
+    }
+
+    #[test]
+    fn given_visible_water_when_turn_info_twice_then_send_water_once() {
+        let mut map = a_map(vec![entity(0, 5, 5)]);
+        map.water = vec![pos(5, 3), pos(5, 17)];
+        let mut game = Game::new(&map, a_game_config(), 1).unwrap();
+
+        let first = game.turn_info(0);
+        let second = game.turn_info(0);
+
+        assert_eq!(first.water, vec![pos(5, 3)]);
+        assert!(second.water.is_empty());
+    }
+
+    #[test]
+    fn given_last_turn_when_finish_turn_then_game_is_over() {
+        let config = Config {
+            turns: 2,
+            ..a_game_config()
+        };
+        let mut game =
+            Game::new(&a_map(vec![entity(0, 2, 2), entity(1, 15, 15)]), config, 1).unwrap();
+
+        game.finish_turn();
+        let over_after_first = game.is_over();
+        game.finish_turn();
+
+        assert!(!over_after_first);
+        assert!(game.is_over());
+    }
+
+    #[test]
+    fn given_raider_and_evading_player_when_play_then_raze_hill_and_both_survive() {
+        let config = Config {
+            turns: 6,
+            ..a_game_config()
+        };
+        let map = a_map(vec![entity(0, 2, 10), entity(1, 6, 10)]);
+        let mut game = Game::new(&map, config, 7).unwrap();
+        let mut raider = ScriptedTurnTaker::new(Direction::E, 4);
+        let mut evader = ScriptedTurnTaker::new(Direction::N, 4);
+
+        let scores = game.play(&mut [&mut raider, &mut evader]).unwrap();
+
+        assert_eq!(scores, vec![3, 0]);
+        assert_eq!(game.hills(), &[entity(0, 2, 10)][..]);
+        assert!(game.ants().contains(&entity(0, 6, 10)));
+        assert!(game.ants().contains(&entity(1, 6, 6)));
+        assert!(game.is_alive(0) && game.is_alive(1));
+        assert_eq!(game.turn(), 7);
+        assert_eq!((raider.calls, evader.calls), (6, 6));
+        assert!(raider.ended && evader.ended);
+    }
+
+    #[test]
+    fn given_turn_taker_overrunning_turn_time_when_play_then_discard_its_orders_and_stop_calling_it(
+    ) {
+        let config = Config {
+            turns: 3,
+            ..a_game_config()
+        };
+        let map = a_map(vec![entity(0, 2, 2), entity(1, 15, 15)]);
+        let mut game = Game::new(&map, config, 7).unwrap();
+        let mut slow = ScriptedTurnTaker {
+            delay: Duration::from_millis(150),
+            ..ScriptedTurnTaker::new(Direction::E, 3)
+        };
+        let mut fast = ScriptedTurnTaker::new(Direction::E, 3);
+
+        game.play(&mut [&mut slow, &mut fast]).unwrap();
+
+        assert!(game.is_timed_out(0));
+        assert!(!game.is_timed_out(1));
+        assert_eq!((slow.calls, fast.calls), (1, 3));
+        assert!(game.ants().contains(&entity(0, 2, 2)));
+        assert!(game.ants().contains(&entity(1, 18, 15)));
+        assert!(!slow.ended && fast.ended);
+    }
+
+    #[test]
+    fn given_fewer_turn_takers_than_players_when_play_then_return_error() {
+        let mut game = Game::new(
+            &a_map(vec![entity(0, 2, 2), entity(1, 15, 15)]),
+            a_game_config(),
+            1,
+        )
+        .unwrap();
+        let mut only = ScriptedTurnTaker::new(Direction::E, 1);
+
+        let result = game.play(&mut [&mut only]);
+
+        assert_eq!(
+            result,
+            Err(GameError::WrongTurnTakerCount {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(only.calls, 0);
+    }
+
+    #[test]
+    fn given_map_without_players_when_new_then_return_error() {
+        let result = Game::new(&a_map(vec![]), a_game_config(), 1);
+
+        assert_matches!(result, Err(GameError::NoPlayers));
+    }
+
+    #[test]
+    fn given_map_with_negative_player_id_when_new_then_return_error() {
+        let mut map = a_map(vec![entity(0, 2, 2)]);
+        map.ants.push(entity(-1, 5, 5));
+
+        let result = Game::new(&map, a_game_config(), 1);
+
+        assert_matches!(result, Err(GameError::InvalidPlayerId { id: -1 }));
+    }
+
+    #[test]
+    fn given_map_with_too_large_player_id_when_new_then_return_error() {
+        let result = Game::new(&a_map(vec![entity(10, 2, 2)]), a_game_config(), 1);
+
+        assert_matches!(result, Err(GameError::InvalidPlayerId { id: 10 }));
+    }
+}
@@ -0,0 +1,435 @@
+use ai::Geometry;
+use ai::Map;
+use ai::PlayerEntity;
+use ai::Position;
+use rand::rngs::StdRng;
+use rand::Rng;
+use rand::SeedableRng;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::fmt;
+
+const CAVE_ITERATIONS: usize = 4;
+const CAVE_WATER_NEIGHBOURS: usize = 5;
+const HILL_CANDIDATES: usize = 200;
+const MAZE_ITERATIONS: usize = 40;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Symmetry {
+    Rotational,
+    Mirrored,
+    Translational,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Terrain {
+    Open,
+    Caves,
+    Maze,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum GeneratorError {
+    UnsupportedSymmetry,
+    NoHillPlacement,
+    TooManyPlayers,
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GeneratorError::UnsupportedSymmetry => {
+                write!(
+                    f,
+                    "symmetry is not supported for this size and player count"
+                )
+            }
+            GeneratorError::NoHillPlacement => write!(f, "cannot place hills on the map"),
+            GeneratorError::TooManyPlayers => {
+                write!(f, "at most {} players are supported", Map::MAX_PLAYERS)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GeneratorError {}
+
+#[derive(Debug, Clone)]
+pub struct MapGenerator {
+    pub width: i32,
+    pub height: i32,
+    pub players: usize,
+    pub symmetry: Symmetry,
+    pub terrain: Terrain,
+    pub water_density: f64,
+    pub hills_per_player: usize,
+}
+
+impl MapGenerator {
+    pub fn generate(&self, seed: u64) -> Result<Map, GeneratorError> {
+        if self.players > Map::MAX_PLAYERS {
+            return Err(GeneratorError::TooManyPlayers);
+        }
+        if !self.is_supported() {
+            return Err(GeneratorError::UnsupportedSymmetry);
+        }
+        let geometry = Geometry::new(self.width, self.height);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut water = match self.terrain {
+            Terrain::Open => self.scatter(&geometry, &mut rng, self.water_density),
+            Terrain::Caves => {
+                let water = self.scatter(&geometry, &mut rng, self.water_density);
+                smooth_caves(&geometry, water)
+            }
+            Terrain::Maze => self.maze(&geometry, &mut rng),
+        };
+        self.connect(&geometry, &mut water);
+
+        let mut hills: Vec<PlayerEntity> = vec![];
+        for _ in 0..self.hills_per_player {
+            let images = self
+                .place_hill(&geometry, &mut rng, &water, &hills)
+                .ok_or(GeneratorError::NoHillPlacement)?;
+            hills.extend(
+                images
+                    .into_iter()
+                    .enumerate()
+                    .map(|(id, pos)| PlayerEntity { id: id as i32, pos }),
+            );
+        }
+
+        let mut map = Map::new(self.width, self.height);
+        map.water = (0..geometry.size())
+            .filter(|&i| water[i])
+            .map(|i| geometry.position(i))
+            .collect();
+        hills.sort_by_key(|h| (h.pos.y, h.pos.x));
+        map.hills = hills;
+        Ok(map)
+    }
+
+    pub fn images(&self, pos: Position) -> Vec<Position> {
+        let (w, h) = (self.width, self.height);
+        let rotate = |p: Position| Position {
+            x: w - 1 - p.y,
+            y: p.x,
+        };
+        let mirror_x = |p: Position| Position {
+            x: w - 1 - p.x,
+            ..p
+        };
+        let mirror_y = |p: Position| Position {
+            y: h - 1 - p.y,
+            ..p
+        };
+        match (self.symmetry, self.players) {
+            (_, 1) => vec![pos],
+            (Symmetry::Rotational, 2) => vec![pos, mirror_x(mirror_y(pos))],
+            (Symmetry::Rotational, 4) => {
+                let mut images = vec![pos];
+                for i in 1..4 {
+                    images.push(rotate(images[i - 1]));
+                }
+                images
+            }
+            (Symmetry::Mirrored, 2) => vec![pos, mirror_x(pos)],
+            (Symmetry::Mirrored, 4) => {
+                vec![pos, mirror_x(pos), mirror_x(mirror_y(pos)), mirror_y(pos)]
+            }
+            (_, n) => (0..n as i32)
+                .map(|p| Position {
+                    x: (pos.x + p * w / n as i32).rem_euclid(w),
+                    y: (pos.y + p * h / n as i32).rem_euclid(h),
+                })
+                .collect(),
+        }
+    }
+
+    fn is_supported(&self) -> bool {
+        let n = self.players as i32;
+        self.width > 0
+            && self.height > 0
+            && match (self.symmetry, self.players) {
+                (_, 0) => false,
+                (_, 1) => true,
+                (Symmetry::Rotational, 2) | (Symmetry::Mirrored, 2) | (Symmetry::Mirrored, 4) => {
+                    true
+                }
+                (Symmetry::Rotational, 4) => self.width == self.height,
+                (Symmetry::Translational, _) => self.width % n == 0 && self.height % n == 0,
+                _ => false,
+            }
+    }
+
+    fn scatter(&self, geometry: &Geometry, rng: &mut StdRng, density: f64) -> Vec<bool> {
+        let mut water = vec![false; geometry.size()];
+        let mut visited = vec![false; geometry.size()];
+        for i in 0..geometry.size() {
+            if visited[i] {
+                continue;
+            }
+            let wet = rng.gen_bool(density.clamp(0.0, 1.0));
+            for image in self.images(geometry.position(i)) {
+                visited[geometry.index(image)] = true;
+                water[geometry.index(image)] = wet;
+            }
+        }
+        water
+    }
+
+    fn maze(&self, geometry: &Geometry, rng: &mut StdRng) -> Vec<bool> {
+        let mut water = self.scatter(geometry, rng, self.water_density);
+        for _ in 0..MAZE_ITERATIONS {
+            water = (0..geometry.size())
+                .map(|i| {
+                    let wet = wet_neighbours(geometry, &water, geometry.position(i));
+                    wet == 3 || (water[i] && (1..=4).contains(&wet))
+                })
+                .collect();
+        }
+        water
+    }
+
+    fn connect(&self, geometry: &Geometry, water: &mut [bool]) {
+        loop {
+            let components = components(geometry, water);
+            let mut sizes: HashMap<usize, usize> = HashMap::new();
+            for &c in components.iter().filter(|&&c| c != usize::MAX) {
+                *sizes.entry(c).or_insert(0) += 1;
+            }
+            let main = match sizes.iter().max_by_key(|&(&c, &size)| (size, c)) {
+                Some((&main, _)) if sizes.len() > 1 => main,
+                _ => return,
+            };
+
+            let mut parents = vec![usize::MAX; geometry.size()];
+            let mut queue: VecDeque<usize> = (0..geometry.size())
+                .filter(|&i| components[i] == main)
+                .collect();
+            queue.iter().for_each(|&i| parents[i] = i);
+            let mut end = None;
+            while let Some(i) = queue.pop_front() {
+                if components[i] != main && components[i] != usize::MAX {
+                    end = Some(i);
+                    break;
+                }
+                for n in geometry.neighbours(geometry.position(i)).iter() {
+                    let n = geometry.index(*n);
+                    if parents[n] == usize::MAX {
+                        parents[n] = i;
+                        queue.push_back(n);
+                    }
+                }
+            }
+
+            let mut i = end.expect("every land component is reachable");
+            while parents[i] != i {
+                i = parents[i];
+                for image in self.images(geometry.position(i)) {
+                    water[geometry.index(image)] = false;
+                }
+            }
+        }
+    }
+
+    fn place_hill(
+        &self,
+        geometry: &Geometry,
+        rng: &mut StdRng,
+        water: &[bool],
+        hills: &[PlayerEntity],
+    ) -> Option<Vec<Position>> {
+        let mut best: Option<(i32, Vec<Position>)> = None;
+        for _ in 0..HILL_CANDIDATES {
+            let candidate = geometry.position(rng.gen_range(0, geometry.size()));
+            let images = self.images(candidate);
+            if images.iter().any(|&p| water[geometry.index(p)]) {
+                continue;
+            }
+            let spread = images
+                .iter()
+                .enumerate()
+                .flat_map(|(i, &a)| {
+                    images[i + 1..]
+                        .iter()
+                        .chain(hills.iter().map(|h| &h.pos))
+                        .map(move |&b| geometry.distance2(a, b))
+                })
+                .min()
+                .unwrap_or(i32::MAX);
+            if spread > 0 && best.as_ref().is_none_or(|(s, _)| spread > *s) {
+                best = Some((spread, images));
+            }
+        }
+        best.map(|(_, images)| images)
+    }
+}
+
+fn smooth_caves(geometry: &Geometry, mut water: Vec<bool>) -> Vec<bool> {
+    for _ in 0..CAVE_ITERATIONS {
+        water = (0..geometry.size())
+            .map(|i| {
+                let wet = wet_neighbours(geometry, &water, geometry.position(i));
+                wet >= CAVE_WATER_NEIGHBOURS || (wet == CAVE_WATER_NEIGHBOURS - 1 && water[i])
+            })
+            .collect();
+    }
+    water
+}
+
+fn wet_neighbours(geometry: &Geometry, water: &[bool], pos: Position) -> usize {
+    (-1..=1)
+        .flat_map(|dy| (-1..=1).map(move |dx| (dx, dy)))
+        .filter(|&(dx, dy)| {
+            let neighbour = Position {
+                x: pos.x + dx,
+                y: pos.y + dy,
+            };
+            (dx, dy) != (0, 0) && water[geometry.index(neighbour)]
+        })
+        .count()
+}
+
+fn components(geometry: &Geometry, water: &[bool]) -> Vec<usize> {
+    let mut components = vec![usize::MAX; geometry.size()];
+    let mut next = 0;
+    for start in 0..geometry.size() {
+        if water[start] || components[start] != usize::MAX {
+            continue;
+        }
+        components[start] = next;
+        let mut queue = VecDeque::from(vec![start]);
+        while let Some(i) = queue.pop_front() {
+            for n in geometry.neighbours(geometry.position(i)).iter() {
+                let n = geometry.index(*n);
+                if !water[n] && components[n] == usize::MAX {
+                    components[n] = next;
+                    queue.push_back(n);
+                }
+            }
+        }
+        next += 1;
+    }
+    components
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn a_generator(players: usize, symmetry: Symmetry, terrain: Terrain) -> MapGenerator {
+        MapGenerator {
+            width: 32,
+            height: 32,
+            players,
+            symmetry,
+            terrain,
+            water_density: 0.4,
+            hills_per_player: 2,
+        }
+    }
+
+    fn assert_symmetric(generator: &MapGenerator, map: &Map) {
+        for &water in &map.water {
+            for image in generator.images(water) {
+                assert!(map.water.contains(&image), "{:?} is not water", image);
+            }
+        }
+        for hill in map.hills.iter().filter(|h| h.id == 0) {
+            for (id, image) in generator.images(hill.pos).into_iter().enumerate() {
+                assert!(map.hills.contains(&PlayerEntity {
+                    id: id as i32,
+                    pos: image
+                }));
+            }
+        }
+    }
+
+    macro_rules! symmetry_tests {
+        ($($name:ident: $players:expr, $symmetry:expr, $terrain:expr,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    let generator = a_generator($players, $symmetry, $terrain);
+
+                    let map = generator.generate(7).unwrap();
+
+                    assert_eq!(map.hills.len(), $players * 2);
+                    assert_eq!(map.players(), $players);
+                    assert_symmetric(&generator, &map);
+                    assert_eq!(Map::parse(&map.to_string()).unwrap(), map);
+                }
+            )*
+        };
+    }
+
+    symmetry_tests! {
+        given_two_players_when_generate_rotational_caves_then_map_is_symmetric: 2, Symmetry::Rotational, Terrain::Caves,
+        given_four_players_when_generate_rotational_maze_then_map_is_symmetric: 4, Symmetry::Rotational, Terrain::Maze,
+        given_two_players_when_generate_mirrored_open_then_map_is_symmetric: 2, Symmetry::Mirrored, Terrain::Open,
+        given_four_players_when_generate_mirrored_caves_then_map_is_symmetric: 4, Symmetry::Mirrored, Terrain::Caves,
+        given_eight_players_when_generate_translational_maze_then_map_is_symmetric: 8, Symmetry::Translational, Terrain::Maze,
+    }
+
+    #[test]
+    fn given_generated_map_when_flood_fill_then_every_hill_is_connected() {
+        let generator = a_generator(4, Symmetry::Mirrored, Terrain::Caves);
+        let map = generator.generate(3).unwrap();
+        let geometry = Geometry::new(map.width, map.height);
+        let mut water = vec![false; geometry.size()];
+        map.water
+            .iter()
+            .for_each(|&w| water[geometry.index(w)] = true);
+
+        let components = components(&geometry, &water);
+
+        let first = components[geometry.index(map.hills[0].pos)];
+        assert!(map
+            .hills
+            .iter()
+            .all(|h| components[geometry.index(h.pos)] == first));
+        assert!(components.iter().all(|&c| c == first || c == usize::MAX));
+    }
+
+    #[test]
+    fn given_same_seed_when_generate_then_return_same_map() {
+        let generator = a_generator(2, Symmetry::Rotational, Terrain::Caves);
+
+        assert_eq!(generator.generate(5), generator.generate(5));
+        assert_ne!(generator.generate(5), generator.generate(6));
+    }
+
+    #[test]
+    fn given_no_water_density_when_generate_open_then_return_no_water() {
+        let generator = MapGenerator {
+            water_density: 0.0,
+            ..a_generator(2, Symmetry::Mirrored, Terrain::Open)
+        };
+
+        let map = generator.generate(1).unwrap();
+
+        assert!(map.water.is_empty());
+    }
+
+    #[test]
+    fn given_unsupported_symmetry_when_generate_then_return_error() {
+        let rotational = a_generator(3, Symmetry::Rotational, Terrain::Open);
+        let translational = a_generator(5, Symmetry::Translational, Terrain::Open);
+
+        assert_eq!(
+            rotational.generate(1),
+            Err(GeneratorError::UnsupportedSymmetry)
+        );
+        assert_eq!(
+            translational.generate(1),
+            Err(GeneratorError::UnsupportedSymmetry)
+        );
+    }
+
+    #[test]
+    fn given_more_than_ten_players_when_generate_then_return_error() {
+        let generator = a_generator(12, Symmetry::Translational, Terrain::Open);
+
+        assert_eq!(generator.generate(1), Err(GeneratorError::TooManyPlayers));
+    }
+}
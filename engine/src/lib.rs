@@ -0,0 +1,24 @@
+mod game;
+mod generator;
+mod process;
+mod protocol;
+#[cfg(test)]
+mod test_support;
+
+pub use ai::Map;
+pub use ai::MapError;
+pub use game::Game;
+pub use game::GameError;
+pub use generator::GeneratorError;
+pub use generator::MapGenerator;
+pub use generator::Symmetry;
+pub use generator::Terrain;
+pub use process::BotProcess;
+pub use protocol::end_lines;
+pub use protocol::parse_order;
+pub use protocol::start_lines;
+pub use protocol::turn_lines;
+
+#[cfg(test)]
+#[macro_use]
+extern crate assert_matches;
@@ -0,0 +1,173 @@
+use crate::protocol;
+use ai::Config;
+use ai::EndInfo;
+use ai::Order;
+use ai::TurnContext;
+use ai::TurnInfo;
+use ai::TurnTaker;
+use std::io;
+use std::io::prelude::*;
+use std::io::BufReader;
+use std::process::Child;
+use std::process::ChildStdin;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+#[derive(Debug)]
+pub struct BotProcess {
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    timed_out: bool,
+}
+
+impl BotProcess {
+    pub fn spawn(command: &mut Command) -> io::Result<Self> {
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdin = child.stdin.take().ok_or(io::ErrorKind::BrokenPipe)?;
+        let stdout = child.stdout.take().ok_or(io::ErrorKind::BrokenPipe)?;
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self {
+            child,
+            stdin,
+            lines,
+            timed_out: false,
+        })
+    }
+
+    pub fn is_timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    fn send(&mut self, lines: Vec<String>) -> io::Result<()> {
+        let mut text = lines.join("\n");
+        text.push('\n');
+        self.stdin.write_all(text.as_bytes())?;
+        self.stdin.flush()
+    }
+
+    fn receive_orders(&mut self, deadline: Option<Instant>) -> Vec<Order> {
+        let mut orders = vec![];
+        loop {
+            let line = match deadline {
+                Some(deadline) => self
+                    .lines
+                    .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                    .ok(),
+                None => self.lines.recv().ok(),
+            };
+            match line {
+                Some(line) if line.trim() == "go" => return orders,
+                Some(line) => orders.extend(protocol::parse_order(&line)),
+                None => {
+                    self.timed_out = true;
+                    return orders;
+                }
+            }
+        }
+    }
+}
+
+impl TurnTaker for BotProcess {
+    fn setup(&mut self, config: &Config) {
+        let deadline = Instant::now() + Duration::from_millis(config.load_time.max(0) as u64);
+        if self.send(protocol::start_lines(config)).is_err() {
+            self.timed_out = true;
+            return;
+        }
+        self.receive_orders(Some(deadline));
+    }
+
+    fn take_turn(&mut self, turn_info: TurnInfo, context: &TurnContext) -> Vec<Order> {
+        if self.timed_out
+            || self
+                .send(protocol::turn_lines(context.turn, &turn_info))
+                .is_err()
+        {
+            return vec![];
+        }
+        self.receive_orders(context.deadline)
+    }
+
+    fn end(&mut self, end_info: EndInfo) {
+        let _ = self.send(protocol::end_lines(&end_info));
+    }
+}
+
+impl Drop for BotProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::a_config;
+    use crate::test_support::entity;
+    use ai::Direction;
+    use ai::OrderDraft;
+
+    const ECHO_BOT: &str = "while read line; do \
+        case $line in \
+        ready) echo go ;; \
+        go) echo 'o 3 2 E'; echo 'garbage'; echo go ;; \
+        esac; done";
+
+    fn a_turn_info() -> TurnInfo {
+        TurnInfo {
+            water: vec![],
+            food: vec![],
+            ant_hill: vec![],
+            ant: vec![entity(0, 2, 3)],
+            dead_ant: vec![],
+        }
+    }
+
+    fn a_context(timeout: Duration) -> TurnContext {
+        TurnContext {
+            turn: 1,
+            turns: 500,
+            deadline: Some(Instant::now() + timeout),
+            draft: OrderDraft::new(),
+        }
+    }
+
+    #[test]
+    fn given_bot_process_when_take_turn_then_return_parsed_orders() {
+        let mut bot = BotProcess::spawn(Command::new("sh").args(["-c", ECHO_BOT])).unwrap();
+        bot.setup(&a_config());
+
+        let orders = bot.take_turn(a_turn_info(), &a_context(Duration::from_secs(5)));
+
+        assert_eq!(orders, vec![Order::new(2, 3, Direction::E)]);
+        assert!(!bot.is_timed_out());
+    }
+
+    #[test]
+    fn given_silent_bot_process_when_take_turn_then_time_out() {
+        let mut bot =
+            BotProcess::spawn(Command::new("sh").args(["-c", "cat > /dev/null"])).unwrap();
+
+        let orders = bot.take_turn(a_turn_info(), &a_context(Duration::from_millis(50)));
+
+        assert!(orders.is_empty());
+        assert!(bot.is_timed_out());
+    }
+}
@@ -0,0 +1,248 @@
+use ai::Config;
+use ai::Direction;
+use ai::EndInfo;
+use ai::Order;
+use ai::PlayerEntity;
+use ai::Position;
+use ai::TurnInfo;
+
+pub fn start_lines(config: &Config) -> Vec<String> {
+    let mut lines = vec![
+        "turn 0".to_string(),
+        format!("loadtime {}", config.load_time),
+        format!("turntime {}", config.turn_time),
+        format!("rows {}", config.height),
+        format!("cols {}", config.width),
+        format!("turns {}", config.turns),
+        format!("viewradius2 {}", config.view_radius2),
+        format!("attackradius2 {}", config.attack_radius2),
+        format!("spawnradius2 {}", config.food_gathering_radius2),
+        format!("player_seed {}", config.player_seed),
+    ];
+    if let Some(players) = config.players {
+        lines.push(format!("players {}", players));
+    }
+    if let Some(cutoff_turn) = config.cutoff_turn {
+        lines.push(format!("cutoff_turn {}", cutoff_turn));
+    }
+    if let Some(cutoff_percent) = config.cutoff_percent {
+        lines.push(format!("cutoff_percent {}", cutoff_percent));
+    }
+    let mut extra: Vec<(&String, &String)> = config.extra.iter().collect();
+    extra.sort();
+    lines.extend(extra.into_iter().map(|(k, v)| format!("{} {}", k, v)));
+    lines.push("ready".to_string());
+    lines
+}
+
+pub fn turn_lines(turn: i32, turn_info: &TurnInfo) -> Vec<String> {
+    let mut lines = vec![format!("turn {}", turn)];
+    lines.extend(entity_lines(turn_info));
+    lines.push("go".to_string());
+    lines
+}
+
+pub fn end_lines(end_info: &EndInfo) -> Vec<String> {
+    let scores: Vec<String> = end_info.scores.iter().map(|s| s.to_string()).collect();
+    let mut lines = vec![
+        "end".to_string(),
+        format!("players {}", end_info.scores.len()),
+        format!("score {}", scores.join(" ")),
+    ];
+    lines.extend(entity_lines(&end_info.turn_info));
+    lines.push("go".to_string());
+    lines
+}
+
+pub fn parse_order(line: &str) -> Option<Order> {
+    let mut l = line.split_whitespace();
+    match (l.next(), l.next(), l.next(), l.next(), l.next()) {
+        (Some("o"), Some(row), Some(col), Some(direction), None) => Some(Order {
+            pos: Position {
+                x: col.parse().ok()?,
+                y: row.parse().ok()?,
+            },
+            direction: parse_direction(direction)?,
+        }),
+        _ => None,
+    }
+}
+
+fn entity_lines(turn_info: &TurnInfo) -> impl Iterator<Item = String> + '_ {
+    let position = |kind: &'static str| move |p: &Position| format!("{} {} {}", kind, p.y, p.x);
+    let player = |kind: &'static str| {
+        move |e: &PlayerEntity| format!("{} {} {} {}", kind, e.pos.y, e.pos.x, e.id)
+    };
+    turn_info
+        .water
+        .iter()
+        .map(position("w"))
+        .chain(turn_info.food.iter().map(position("f")))
+        .chain(turn_info.ant_hill.iter().map(player("h")))
+        .chain(turn_info.ant.iter().map(player("a")))
+        .chain(turn_info.dead_ant.iter().map(player("d")))
+}
+
+fn parse_direction(direction: &str) -> Option<Direction> {
+    match direction {
+        "N" => Some(Direction::N),
+        "E" => Some(Direction::E),
+        "S" => Some(Direction::S),
+        "W" => Some(Direction::W),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::a_config;
+    use crate::test_support::entity;
+    use crate::test_support::pos;
+    use ai::TurnContext;
+    use ai::TurnTaker;
+    use client::Client;
+    use client::ParseMode;
+    use std::io;
+
+    fn a_two_player_config() -> Config {
+        Config {
+            height: 30,
+            players: Some(2),
+            ..a_config()
+        }
+    }
+
+    fn a_turn_info() -> TurnInfo {
+        TurnInfo {
+            water: vec![pos(6, 7)],
+            food: vec![pos(4, 7)],
+            ant_hill: vec![entity(1, 12, 7)],
+            ant: vec![entity(0, 9, 10), entity(1, 10, 11)],
+            dead_ant: vec![entity(1, 12, 15)],
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorder {
+        config: Option<Config>,
+        turns: Vec<(i32, TurnInfo)>,
+        end: Option<EndInfo>,
+    }
+
+    impl TurnTaker for Recorder {
+        fn setup(&mut self, config: &Config) {
+            self.config = Some(config.clone());
+        }
+
+        fn take_turn(&mut self, turn_info: TurnInfo, context: &TurnContext) -> Vec<Order> {
+            self.turns.push((context.turn, turn_info));
+            vec![]
+        }
+
+        fn end(&mut self, end_info: EndInfo) {
+            self.end = Some(end_info);
+        }
+    }
+
+    #[test]
+    fn given_turn_info_when_turn_lines_then_emit_protocol_lines() {
+        let lines = turn_lines(3, &a_turn_info());
+
+        assert_eq!(
+            lines,
+            vec![
+                "turn 3",
+                "w 7 6",
+                "f 7 4",
+                "h 7 12 1",
+                "a 10 9 0",
+                "a 11 10 1",
+                "d 15 12 1",
+                "go"
+            ]
+        );
+    }
+
+    #[test]
+    fn given_end_info_when_end_lines_then_emit_players_and_score() {
+        let end_info = EndInfo {
+            scores: vec![3, 1],
+            turn_info: a_turn_info(),
+        };
+
+        let lines = end_lines(&end_info);
+
+        assert_eq!(lines[..3], ["end", "players 2", "score 3 1"]);
+        assert_eq!(lines.last().unwrap(), "go");
+    }
+
+    #[test]
+    fn given_order_lines_when_parse_order_then_return_order_or_none() {
+        assert_eq!(
+            parse_order("o 3 2 W"),
+            Some(Order {
+                pos: pos(2, 3),
+                direction: Direction::W,
+            })
+        );
+        assert_eq!(parse_order("o 3 2 X"), None);
+        assert_eq!(parse_order("o 3 2"), None);
+        assert_eq!(parse_order("go"), None);
+    }
+
+    #[test]
+    fn given_emitted_lines_when_parsed_by_strict_client_then_round_trip() {
+        let config = a_two_player_config();
+        let end_info = EndInfo {
+            scores: vec![3, 1],
+            turn_info: a_turn_info(),
+        };
+        let input = start_lines(&config)
+            .into_iter()
+            .chain(turn_lines(1, &a_turn_info()))
+            .chain(end_lines(&end_info));
+        let mut client = Client::with_mode(input, io::sink(), ParseMode::Strict);
+        let mut recorder = Recorder::default();
+
+        client.set_up().unwrap();
+        client.run(&mut recorder).unwrap();
+
+        let parsed = recorder.config.unwrap();
+        assert_eq!(parsed.width, 20);
+        assert_eq!(parsed.height, 30);
+        assert_eq!(parsed.players, Some(2));
+        assert!(parsed.extra.is_empty());
+        assert_eq!(recorder.turns.len(), 1);
+        let (turn, turn_info) = &recorder.turns[0];
+        assert_eq!(*turn, 1);
+        assert_eq!(turn_info.water, a_turn_info().water);
+        assert_eq!(turn_info.food, a_turn_info().food);
+        assert_eq!(turn_info.ant_hill, a_turn_info().ant_hill);
+        assert_eq!(turn_info.ant, a_turn_info().ant);
+        assert_eq!(turn_info.dead_ant, a_turn_info().dead_ant);
+        assert_matches!(recorder.end, Some(EndInfo { scores, .. }) if scores == vec![3, 1]);
+    }
+
+    #[test]
+    fn given_config_with_extra_keys_when_parsed_by_strict_client_then_keep_extra() {
+        let mut config = a_two_player_config();
+        config
+            .extra
+            .insert("food_rate".to_string(), "5 11".to_string());
+        config
+            .extra
+            .insert("food_turn".to_string(), "19".to_string());
+        let input = start_lines(&config).into_iter().chain(end_lines(&EndInfo {
+            scores: vec![0, 0],
+            turn_info: a_turn_info(),
+        }));
+        let mut client = Client::with_mode(input, io::sink(), ParseMode::Strict);
+        let mut recorder = Recorder::default();
+
+        client.set_up().unwrap();
+        client.run(&mut recorder).unwrap();
+
+        assert_eq!(recorder.config.unwrap().extra, config.extra);
+    }
+}
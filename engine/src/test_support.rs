@@ -0,0 +1,30 @@
+use ai::Config;
+use ai::PlayerEntity;
+use ai::Position;
+use std::collections::HashMap;
+
+pub fn pos(x: i32, y: i32) -> Position {
+    Position { x, y }
+}
+
+pub fn entity(id: i32, x: i32, y: i32) -> PlayerEntity {
+    PlayerEntity { id, pos: pos(x, y) }
+}
+
+pub fn a_config() -> Config {
+    Config {
+        load_time: 3000,
+        turn_time: 1000,
+        width: 20,
+        height: 20,
+        turns: 500,
+        view_radius2: 55,
+        attack_radius2: 5,
+        food_gathering_radius2: 1,
+        player_seed: 42,
+        players: None,
+        cutoff_turn: None,
+        cutoff_percent: None,
+        extra: HashMap::new(),
+    }
+}